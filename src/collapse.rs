@@ -23,45 +23,98 @@ impl Collapser {
 
         loop {
             let cur = self.cur();
-            let ast = match cur {
-                Ast::Char(_) |
-                Ast::Literal(_) => Some(cur),
-                Ast::Class(mut deque) => Some(self.collapse_class(&mut deque)),
-                Ast::Empty => None,
-                _ => unimplemented!(),
-            };
 
-            if let Some(val) = ast { vec.push(val) }
+            if let Some(val) = self.collapse_ast(cur) { vec.push(val) }
 
             if !self.next() { break }
         }
 
         self.data = vec;
     }
-    // I think this entire thing might be replaceable with a fold but
-    // I was running into issues when testing it. `chunks` returns references
-    // and DequeVec doesn't implement Deref so I can't call chunks on it. Maybe
-    // in the future.
-    fn collapse_class(&mut self, deque: &mut VecDeque<Ast>) -> Ast {
-        let mut left = deque.pop_front()
-                            .unwrap();
-
-        while let Some(op) = deque.pop_front() {
-            let right = deque.pop_front()
-                             .unwrap();
-
-            left = match op {
-                Ast::Op(op) => op.apply(left, right),
-                // Only operators should ever appear here.
-                _ => unreachable!(),
-            };
+    // Collapse a single `Ast`. `None` means it had no effect and can be dropped.
+    fn collapse_ast(&mut self, ast: Ast) -> Option<Ast> {
+        match ast {
+            Ast::Anchor(_) |
+            Ast::Char(_) |
+            Ast::Dot |
+            Ast::Folded(..) |
+            Ast::Literal(_) |
+            Ast::Newline |
+            Ast::Set(..) => Some(ast),
+            Ast::Alternation(branches, choice) => {
+                let branches = branches.into_iter()
+                                       .map(|branch| self.collapse_vec(branch))
+                                       .collect();
+
+                Some(Ast::Alternation(branches, choice))
+            },
+            Ast::Class(mut deque) => Some(self.collapse_class(&mut deque)),
+            Ast::Group(vec, faction) => Some(Ast::Group(self.collapse_vec(vec), faction)),
+            Ast::Lookaround(vec, look, polarity) => {
+                Some(Ast::Lookaround(self.collapse_vec(vec), look, polarity))
+            },
+            Ast::NamedCapture(name, ast) => {
+                self.collapse_ast(*ast)
+                    .map(|ast| Ast::NamedCapture(name, Box::new(ast)))
+            },
+            Ast::Repeat(ast, min, max) => {
+                self.collapse_ast(*ast)
+                    .map(|ast| Ast::Repeat(Box::new(ast), min, max))
+            },
+            Ast::Empty => None,
+            _ => unimplemented!(),
         }
+    }
+    fn collapse_vec(&mut self, v: Vec<Ast>) -> Vec<Ast> {
+        v.into_iter()
+         .filter_map(|ast| self.collapse_ast(ast))
+         .collect()
+    }
+    fn collapse_class(&mut self, deque: &mut VecDeque<Ast>) -> Ast {
+        let left = self.collapse_operand(deque);
+        let left = self.collapse_ops(left, 0, deque);
+        // The parser made sure sets and ops alternate so every one was used.
+        debug_assert!(deque.is_empty());
 
         // Empty intersections like `< & [a] >` are not allowed.
         if let Ast::Empty = left { panic!("An empty class `<[]>` is not allowed!") }
 
         left
     }
+    // Apply every op which binds at least as tightly as `min` to `left`. When
+    // the op after the next operand binds tighter, that operand is collapsed
+    // with it first so `[a] + [b] & [c]` is `[a] + ([b] & [c])`.
+    fn collapse_ops(&mut self, mut left: Ast, min: u8, deque: &mut VecDeque<Ast>) -> Ast {
+        while let Some(&Ast::Op(op)) = deque.front() {
+            if op.precedence() < min { break }
+            deque.pop_front();
+
+            let mut right = self.collapse_operand(deque);
+
+            while let Some(&Ast::Op(next)) = deque.front() {
+                if next.precedence() <= op.precedence() { break }
+
+                right = self.collapse_ops(right, next.precedence(), deque);
+            }
+
+            left = op.apply(left, right);
+        }
+
+        left
+    }
+    // Pop the next operand. A parenthesized `Class` is collapsed on its own.
+    fn collapse_operand(&mut self, deque: &mut VecDeque<Ast>) -> Ast {
+        match deque.pop_front().unwrap() {
+            Ast::Class(mut deque) => {
+                let left = self.collapse_operand(&mut deque);
+
+                self.collapse_ops(left, 0, &mut deque)
+            },
+            // Only operands should ever appear here.
+            Ast::Op(_) => unreachable!(),
+            ast => ast,
+        }
+    }
     fn next(&mut self) -> bool {
         self.pos += 1;
 
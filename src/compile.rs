@@ -0,0 +1,335 @@
+use parse::{Anchor, Ast, Fold};
+use parse::Choice::{self, Longest, Sequential};
+use parse::Faction::{self, Capture, NonCapture};
+use parse::Look::{Ahead, Behind};
+use parse::Membership::Inclusive;
+use parse::Polarity;
+use trie::Trie;
+
+// A single step of a `Program`. The `vm` runs these one at a time and
+// backtracks to the most recent `Split` whenever one fails.
+#[derive(Clone, Debug)]
+pub enum Inst {
+    // Zero width. Check if the program matches starting here.
+    Ahead(Box<Program>, Polarity),
+    Assert(Anchor),         // Zero width. Check where in the text this is
+    Atom(Ast),              // Match a `Char`, `Dot`, `Folded`, `Literal`, `Newline` or `Set` and advance
+    Back(Ast),              // Match an atom which ends here and move back to where it starts
+    // Zero width. Check if the program matches ending here. It was compiled
+    // back to front so it runs leftward from here.
+    Behind(Box<Program>, Polarity),
+    Jump(usize),            // Continue at another instruction
+    Longest(Vec<Branch>),   // Try branches with the longest prefix match first
+    Match,                  // Everything before this has matched
+    Save(usize),            // Record the position in a capture slot
+    Split(usize, usize),    // Try the first branch then fall back to the second
+    // Match the longest word which starts here once the text is folded.
+    // Shorter ones are tried when backtracking.
+    Words(Trie, Fold),
+}
+
+// A branch of a `|` alternation. Only the declarative `prefix` of the
+// branch is used to decide which branch has the longest token.
+#[derive(Clone, Debug)]
+pub struct Branch {
+    pub prefix: Program,
+    pub goto: usize,
+}
+
+#[derive(Clone, Debug)]
+pub struct Program {
+    pub insts: Vec<Inst>,
+    // Where a match could start depends only on the first `Ast` so
+    // `MatchStart` uses it to skip ahead.
+    pub start: Option<Ast>,
+    // The number of positional captures. Capture `n` saves its start in slot
+    // `2 * n` and its end in slot `2 * n + 1`.
+    pub captures: usize,
+    // Named captures in the order they first appear. Their slots follow the
+    // positional ones.
+    pub names: Vec<String>,
+}
+
+// Perl 6 only uses the declarative start of a `|` branch to find the longest
+// token. It ends at the first thing which isn't declarative such as a `||`.
+fn declarative(v: &[Ast]) -> Vec<Ast> {
+    let mut prefix = vec![];
+
+    for ast in v {
+        if is_declarative(ast) {
+            prefix.push(ast.clone());
+            continue
+        }
+
+        // The declarative start of a group is still part of the prefix.
+        if let Ast::Group(ref vec, faction) = *ast { prefix.push(Ast::Group(declarative(vec), faction)) }
+        break
+    }
+
+    prefix
+}
+
+fn is_declarative(ast: &Ast) -> bool {
+    match *ast {
+        Ast::Alternation(_, Sequential) |
+        Ast::Lookaround(..) => false,
+        Ast::Alternation(ref branches, Longest) => {
+            branches.iter().all(|branch| branch.iter().all(is_declarative))
+        },
+        Ast::Group(ref vec, _) => vec.iter().all(is_declarative),
+        Ast::NamedCapture(_, ref ast) |
+        Ast::Repeat(ref ast, _, _) => is_declarative(ast),
+        _ => true,
+    }
+}
+
+// A `|` alternation where every branch is a single literal, such as a
+// `< big small >` word list, only needs to find the longest word which starts
+// here. A `Trie` finds every one of them in one pass. The literals must all be
+// folded the same way since the text is only folded once.
+fn words(branches: &[Vec<Ast>]) -> Option<(Trie, Fold)> {
+    let mut trie = Trie::new();
+    let fold = match branches[0][..] {
+        [Ast::Folded(_, fold)] => fold,
+        _ => Fold::default(),
+    };
+
+    for branch in branches {
+        match branch[..] {
+            [Ast::Literal(ref s)] if fold == Fold::default() => trie.insert(s),
+            [Ast::Folded(ref s, f)] if f == fold => trie.insert(s),
+            _ => return None,
+        }
+    }
+
+    Some((trie, fold))
+}
+
+// `MatchStart` searches for the first `Ast` at every position it tries. For
+// a word list that means searching for every word so the set of chars the
+// words start with is searched for instead.
+fn start(ast: &Ast) -> Ast {
+    match *ast {
+        Ast::Alternation(ref branches, Longest) => match words(branches) {
+            Some((trie, fold)) => Ast::Set(fold.starts(&trie.first_chars()), Inclusive),
+            None => ast.clone(),
+        },
+        _ => ast.clone(),
+    }
+}
+
+// Count the positional captures in `ast` the way Perl 6 numbers them. Each
+// branch of an alternation starts counting from the same number so the
+// branch with the most captures decides. A capture nested inside another
+// capture belongs to the outer one so it isn't counted.
+fn count_captures(ast: &Ast) -> usize {
+    match *ast {
+        Ast::Alternation(ref branches, _) => {
+            branches.iter()
+                    .map(|branch| branch.iter().map(count_captures).sum())
+                    .max()
+                    .unwrap_or(0)
+        },
+        Ast::Group(_, Capture) => 1,
+        Ast::Group(ref vec, NonCapture) => vec.iter().map(count_captures).sum(),
+        // Named captures aren't numbered.
+        Ast::Repeat(ref ast, _, _) => count_captures(ast),
+        _ => 0,
+    }
+}
+
+pub fn compile(v: &[Ast]) -> Program { compile_with(v, false) }
+
+// A lookbehind is compiled `backward`. Its program starts from the end of
+// the text it matches and runs leftward. Only whether it matches is ever
+// needed so which branch of a `|` is longest doesn't matter.
+fn compile_with(v: &[Ast], backward: bool) -> Program {
+    let mut c = Compiler { insts: vec![],
+                           captures: 0,
+                           positional: v.iter().map(count_captures).sum(),
+                           names: vec![],
+                           in_capture: false,
+                           backward,
+    };
+
+    c.compile_all(v);
+    c.push(Inst::Match);
+
+    Program { insts: c.insts,
+              start: if backward { None } else { v.first().map(start) },
+              captures: c.positional,
+              names: c.names,
+    }
+}
+
+struct Compiler {
+    insts: Vec<Inst>,
+    // The number the next capture will get.
+    captures: usize,
+    // The total number of positional captures.
+    positional: usize,
+    names: Vec<String>,
+    // Inside of a capture, nested captures don't get numbers of their own.
+    in_capture: bool,
+    // Match from right to left.
+    backward: bool,
+}
+
+impl Compiler {
+    fn compile(&mut self, ast: &Ast) {
+        match *ast {
+            Ast::Char(_) |
+            Ast::Dot |
+            Ast::Folded(..) |
+            Ast::Literal(_) |
+            Ast::Newline |
+            Ast::Set(..) => {
+                let atom = ast.clone();
+                self.push(if self.backward { Inst::Back(atom) } else { Inst::Atom(atom) });
+            },
+            Ast::Alternation(ref branches, choice) => self.compile_alternation(branches, choice),
+            Ast::Anchor(anchor) => { self.push(Inst::Assert(anchor)); },
+            Ast::Group(ref vec, faction) => self.compile_group(vec, faction),
+            // What a lookaround captures isn't kept so it gets a program of its own.
+            Ast::Lookaround(ref vec, Ahead, polarity) => {
+                self.push(Inst::Ahead(Box::new(compile(vec)), polarity));
+            },
+            Ast::Lookaround(ref vec, Behind, polarity) => {
+                self.push(Inst::Behind(Box::new(compile_with(vec, true)), polarity));
+            },
+            Ast::NamedCapture(ref name, ref ast) => self.compile_named(name, ast),
+            Ast::Repeat(ref ast, min, max) => self.compile_repeat(ast, min, max),
+            _ => unimplemented!(),
+        }
+    }
+    // A sequence of `Ast`s. Backwards the last one is matched first.
+    fn compile_all(&mut self, v: &[Ast]) {
+        if self.backward { for ast in v.iter().rev() { self.compile(ast) } }
+        else { for ast in v { self.compile(ast) } }
+    }
+    // Every branch is compiled one after another and jumps to the end when
+    // it's done. `||` chains `Split`s so each branch falls back to the next.
+    // `|` starts with a `Longest` which picks the order at match time.
+    fn compile_alternation(&mut self, branches: &[Vec<Ast>], choice: Choice) {
+        let choice = if self.backward { Sequential } else { choice };
+
+        if choice == Longest {
+            if let Some((trie, fold)) = words(branches) {
+                self.push(Inst::Words(trie, fold));
+                return
+            }
+        }
+
+        let longest = if choice == Longest { Some(self.push(Inst::Longest(vec![]))) } else { None };
+        let (mut gotos, mut jumps) = (vec![], vec![]);
+
+        let (first_capture, mut captures) = (self.captures, self.captures);
+
+        for (i, branch) in branches.iter().enumerate() {
+            let last = i == branches.len() - 1;
+            // Every branch numbers its captures from the same place.
+            self.captures = first_capture;
+            let split = if choice == Sequential && !last { Some(self.push(Inst::Split(0, 0))) }
+                        else { None };
+
+            gotos.push(self.insts.len());
+            self.compile_all(branch);
+            if !last { jumps.push(self.push(Inst::Jump(0))) }
+            captures = captures.max(self.captures);
+
+            if let Some(split) = split { self.insts[split] = Inst::Split(split + 1, self.insts.len()) }
+        }
+
+        let end = self.insts.len();
+        for jump in jumps { self.insts[jump] = Inst::Jump(end) }
+        self.captures = captures;
+
+        if let Some(longest) = longest {
+            self.insts[longest] = Inst::Longest(branches.iter()
+                .zip(gotos)
+                .map(|(branch, goto)| Branch { prefix: compile(&declarative(branch)), goto })
+                .collect());
+        }
+    }
+    fn compile_group(&mut self, vec: &[Ast], faction: Faction) {
+        if faction == NonCapture || self.in_capture { return self.compile_all(vec) }
+
+        let slot = 2 * self.captures;
+        let (first, last) = if self.backward { (slot + 1, slot) } else { (slot, slot + 1) };
+        self.captures += 1;
+        self.in_capture = true;
+
+        self.push(Inst::Save(first));
+        self.compile_all(vec);
+        self.push(Inst::Save(last));
+
+        self.in_capture = false;
+    }
+    // The same name used twice shares one pair of slots.
+    fn compile_named(&mut self, name: &str, ast: &Ast) {
+        if self.in_capture { return self.compile(ast) }
+
+        let index = match self.names.iter().position(|n| n == name) {
+            Some(index) => index,
+            None => {
+                self.names.push(name.to_owned());
+                self.names.len() - 1
+            },
+        };
+        let slot = 2 * (self.positional + index);
+        let (first, last) = if self.backward { (slot + 1, slot) } else { (slot, slot + 1) };
+
+        // A named `( )` is only named so it needs no number either.
+        self.in_capture = true;
+
+        self.push(Inst::Save(first));
+        self.compile(ast);
+        self.push(Inst::Save(last));
+
+        self.in_capture = false;
+    }
+    // Every copy of a repeated atom shares the same capture numbers.
+    fn compile_copy(&mut self, ast: &Ast, first_capture: usize) {
+        self.captures = first_capture;
+        self.compile(ast);
+    }
+    // `a ** 2..4` becomes `a a a? a?` where each optional `a` is only
+    // tried when the one before it matched. Repetitions are greedy so a
+    // `Split` always prefers another `a` over skipping to the end.
+    fn compile_repeat(&mut self, ast: &Ast, min: usize, max: Option<usize>) {
+        let first_capture = self.captures;
+
+        for _ in 0..min { self.compile_copy(ast, first_capture) }
+
+        match max {
+            None => {
+                let split = self.push(Inst::Split(0, 0));
+                self.compile_copy(ast, first_capture);
+                self.push(Inst::Jump(split));
+
+                let end = self.insts.len();
+                self.insts[split] = Inst::Split(split + 1, end);
+            },
+            Some(max) => {
+                let mut splits = vec![];
+
+                for _ in min..max {
+                    splits.push(self.push(Inst::Split(0, 0)));
+                    self.compile_copy(ast, first_capture);
+                }
+
+                let end = self.insts.len();
+                for split in splits { self.insts[split] = Inst::Split(split + 1, end) }
+            },
+        }
+
+        // `** 0` has no copies but its captures are still numbered.
+        if !self.in_capture { self.captures = first_capture + count_captures(ast) }
+    }
+    // Append an instruction and return its index.
+    fn push(&mut self, inst: Inst) -> usize {
+        self.insts.push(inst);
+
+        self.insts.len() - 1
+    }
+}
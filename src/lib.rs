@@ -10,8 +10,11 @@
 //!
 //! # Note
 //!
-//! The only real currently available method is [`is_match()`]
-//! (re/struct.Regex.html#method.is_match).
+//! The currently available methods are [`is_match()`]
+//! (re/struct.Regex.html#method.is_match), [`find()`]
+//! (re/struct.Regex.html#method.find), [`find_iter()`]
+//! (re/struct.Regex.html#method.find_iter) and [`captures()`]
+//! (re/struct.Regex.html#method.captures).
 //!
 //! This is designed to operate on Unicode scalar values.
 //!
@@ -44,6 +47,93 @@
 //! it\'s\ my\ birthday // Matches `it's my birthday`.
 //! ```
 //!
+//! Escaped letters and digits have special meanings so only the ones listed
+//! here and under character classes are allowed:
+//!
+//! Escape                 | Matches
+//! -----------------------|--------
+//! `\x41` or `\x[263A]`   | The char with that hex codepoint
+//! `\o101` or `\o[101]`   | The char with that octal codepoint
+//! `\c65` or `\c[65]`     | The char with that decimal codepoint
+//! `\c[GREEK SMALL LETTER ALPHA]` | The char with that Unicode name or alias
+//! `\c@`, `\cA` ... `\c_` | A control char. `\c?` is delete
+//! `\r`, `\f`, `\e`, `\0`   | Carriage return, form feed, escape and null
+//!
+//! # Quantifiers
+//!
+//! A quantifier follows an atom and sets how many times it may repeat:
+//!
+//! Quantifier | Repeats
+//! -----------|--------
+//! `*`        | Zero or more times
+//! `+`        | One or more times
+//! `?`        | Zero or one time
+//! `** 3`     | Exactly 3 times
+//! `** 2..5`  | From 2 to 5 times
+//! `** 2..*`  | 2 or more times
+//!
+//! ```plain
+//! \d+         // Matches `1`, `12` or `123`.
+//! colou?r     // Matches `color` or `colour`.
+//! \d ** 2..3  // Matches `12` or `123`.
+//! ```
+//!
+//! # Alternation
+//!
+//! `||` tries each branch in order and takes the first which matches. `|`
+//! uses longest token matching instead: the branch whose declarative prefix
+//! matches the most text is tried first. Branches of equal length are tried
+//! in the order they were written. The declarative prefix of a branch ends at
+//! anything which isn't declarative such as a `||`.
+//!
+//! ```plain
+//! 'if' | \w+        // Matches all of `iffy`.
+//! 'if' || \w+       // Matches only the `if` of `iffy`.
+//! a | b || c        // Same as `[ a | b ] || c`.
+//! ```
+//!
+//! # Groups
+//!
+//! `[ ]` groups atoms together without capturing them and `( )` groups them
+//! and captures what they matched as `$0`, `$1`, ... Either one is treated as
+//! a single atom so it can be quantified or used to limit an alternation.
+//!
+//! ```plain
+//! [ 'na' ]+ ' batman'  // Matches `nanana batman`.
+//! '<' [ b | i ] '>'    // Matches `<b>` or `<i>`.
+//! ( \d+ ) '%'          // Matches and captures the `50` in `50%`.
+//! ```
+//!
+//! `$<name>=` names a capture instead of numbering it:
+//!
+//! ```plain
+//! $<year>=[\d**4] '-' $<month>=(\d**2)  // `2015-06` captures `year` and `month`.
+//! ```
+//!
+//! # Anchors
+//!
+//! Anchors match a position instead of text:
+//!
+//! Anchor | Matches at
+//! -------|-----------
+//! `^`    | The start of the string
+//! `$`    | The end of the string
+//! `^^`   | The start of a line
+//! `$$`   | The end of a line
+//! `<<` or `«` | The start of a word
+//! `>>` or `»` | The end of a word
+//! `<wb>` | Either end of a word
+//!
+//! ```plain
+//! ^ \w+ $     // Matches only if the whole string is a word.
+//! ^^ '#'      // Matches `#` at the start of any line.
+//! << 'if' >>  // Matches `if` but not the start of `iffy`.
+//! ```
+//!
+//! Lines are split by any newline `\n` matches so `\r\n` works the same as
+//! `\n`. A newline at the very end of the string doesn't start another line.
+//! A word is made of the chars `\w` matches.
+//!
 //! # Chevrons: `<>`
 //!
 //! Chevrons are considered a metacharacter grouping operator whose behaviour
@@ -57,9 +147,17 @@
 //! `?` | `<?before foo>` | A positive zero width assertion
 //! `!` | `<!before foo>` | A negative zero width assertion
 //! `[` | `<[ ab ]>` | A character class matches `[ 'a' | 'b' ]`
-//! `-` | `<-[a] + [b]>` | Negated character class: `[ab]` negated
+//! `:` | `<:Lu>` | A Unicode property
+//! `-` | `<-[a] - [b]>` | Negated character class: `[ab]` negated
 //! `+` | `<+ [a] >` | Doesn't modify the class.
 //!
+//! A word list matches the longest of its words the way `|` does, so
+//! `< in int >` matches all of `int`. The words are searched for together so a
+//! list can hold thousands of them. Anything which starts with `[`, `(`, `:`
+//! or a set operator is still a class so `< [a] + [b] >` isn't a word list.
+//! Neither is a name with a spaced out set operator and another operand after
+//! it like `< alpha + [_] >`, though `< upper-deck >` is.
+//!
 //! # Lookaround
 //!
 //! * lookahead - `foo <?after bar>` matches `foo` in `foobar`
@@ -70,6 +168,13 @@
 //! An example with both:
 //! `<?before foo> bar <?after baz>`  matches `bar` in `foobarbaz`
 //!
+//! This is the reverse of Perl 6 (see `perl_changes.md`). [`RegexBuilder::raku_lookaround()`]
+//! (re/struct.RegexBuilder.html#method.raku_lookaround) switches to the Perl 6
+//! meaning so patterns can be shared between the two.
+//!
+//! Anything can go inside a lookaround. It never becomes part of the match
+//! and whatever it captures is thrown away.
+//!
 //! # Set operators
 //!
 //! These operators can be applied to groups which will be analyzed later:
@@ -82,20 +187,81 @@
 //! ^       Symmetric difference // [123] ^ [345] = [1245]
 //! ```
 //!
+//! Anything which matches a single char can be an operand: `[]` sets,
+//! Unicode properties like `:L` and named character classes like `alpha`.
+//! `<:L - [a..z]>` is any letter except the ASCII lowercase ones,
+//! `<alpha + [_-]>` adds `-` to `alpha` and `<+digit -[0]>` is a digit other
+//! than `0`. Negated operands such as `<:!L>` or `[\S]` work the same way.
+//!
+//! `&` binds tighter than the others. `+`, `|`, `-` and `^` share a level and
+//! apply left to right. Parentheses group a part of the class so it's
+//! applied first:
+//!
+//! ```plain
+//! <[a..z] - [aeiou] + [e]>           // Consonants and `e`
+//! <[a..z] - [aeiou] & [a..f]>        // `[a..z] - [ae]`
+//! <[a..z] - ([aeiou] + [y])>         // Consonants without `y`
+//! ```
+//!
 //! # Character classes
 //!
 //! ## Default character classes
 //!
 //! Character | Matches               | Inverse
 //! ----------|-----------------------|--------
-//! `.`       | Any character         | N/A
+//! `.`       | Any character, even a newline | N/A
 //! `\d`      | Digit                 | `\D`
 //! `\h`      | Horizontal whitespace | `\H`
-//! `\n`      | Newline               | `\N`
+//! `\n`      | Newline, even `\r\n`  | `\N`
 //! `\s`      | Any whitespace        | `\S`
 //! `\t`      | Tab                   | `\T`
+//! `\v`      | Vertical whitespace   | `\V`
 //! `\w`      | Alphanumeric or `_`   | `\W`
 //!
+//! `\r\n` is matched by a single `\n` and never split. Inside `<[ ]>` a `\n`
+//! only matches one char so it matches either half.
+//!
+//! ## Named character classes
+//!
+//! Name     | Matches
+//! ---------|--------
+//! `alpha`  | Alphabetic or `_`
+//! `alnum`  | `alpha` or a digit
+//! `blank`  | Horizontal whitespace
+//! `cntrl`  | Control chars
+//! `digit`  | Digit
+//! `graph`  | Anything but whitespace, controls and unassigned chars
+//! `lower`  | Lowercase
+//! `print`  | `graph` or horizontal whitespace which isn't a control
+//! `punct`  | Punctuation or an ASCII symbol
+//! `space`  | Any whitespace
+//! `upper`  | Uppercase
+//! `xdigit` | Hex digit
+//! `ident`  | An identifier: `alpha` followed by any number of `\w`
+//! `ws`     | Whitespace which is required only between two word chars
+//!
+//! `<alpha>` captures what it matched as `$<alpha>`. `<.alpha>` doesn't
+//! capture.
+//!
+//! ```plain
+//! <ident> <.ws> '=' <.ws> <digit>+  // `x = 42` captures `ident` and `digit`.
+//! ```
+//!
+//! ## Unicode properties
+//!
+//! `<:Name>` matches a char with a Unicode property which may be a general
+//! category, a script or a binary property like `Alphabetic`. `<:!Name>`
+//! matches any char without it. `<:Script<Name>>` and `<:General_Category<Name>>`
+//! (or `sc` and `gc`) only look for that kind of property. Names are matched
+//! loosely so case, spaces, `_`, `-` and a leading `Is` don't matter.
+//!
+//! ```plain
+//! <:Lu>                    // An uppercase letter.
+//! <:gc<Uppercase_Letter>>  // Same
+//! <:!L>                    // Anything but a letter.
+//! <:Script<Greek>>+        // Matches `λόγος`.
+//! ```
+//!
 //! ## Custom character classes
 //!
 //! Characters inside a set of `<[ ]>` form a custom character
@@ -115,6 +281,48 @@
 //! <\d - [13579]>    // Same
 //! ```
 //!
+//! # Adverbs
+//!
+//! An adverb changes how the rest of the group it's in is matched. `:!` turns
+//! one back off.
+//!
+//! Adverb | Long form | Effect
+//! -------|-----------|-------
+//! `:i` | `:ignorecase` | Case is ignored using Unicode case folding
+//! `:m` | `:ignoremark` | Combining marks are ignored so `e` matches `é`
+//! `:s` | `:sigspace` | Whitespace after an atom matches `<.ws>`
+//!
+//! ```plain
+//! :i 'hello'        // Matches `hello`, `HELLO` or `HeLLo`.
+//! [ :i a ] b        // Matches `ab` or `Ab` but not `aB`.
+//! :i <[a..f]>       // Matches `a` through `f` and `A` through `F`.
+//! ```
+//!
+//! `:i` uses full case folding so `:i 'strasse'` matches `Straße` and `:i 'fi'`
+//! matches `ﬁ`. A match never ends partway through a char though, so `:i s`
+//! doesn't match `ß`. A character class matches one char at a time so it only
+//! uses the one to one foldings.
+//!
+//! `:m` decomposes chars and drops their marks so `:m 'Jose'` matches `José`
+//! whether the `é` is a single char or `e` followed by `\x[301]`. The marks
+//! after a char are matched along with it, whether the char is in a literal or
+//! a character class.
+//!
+//! `:s` makes whitespace mean something again. Whitespace after an atom,
+//! including after its quantifier, matches `<.ws>` which is optional
+//! whitespace that's required between two word chars. Whitespace before the
+//! first atom or before a quantifier is still ignored.
+//!
+//! ```plain
+//! :s ^ <ident> '=' \d+ $   // Matches `size=10` or `  size = 10 `.
+//! :s 'key' 'value'         // Matches `key value` but not `keyvalue`.
+//! ```
+//!
+//! Matches are still reported in the text as it was given. [`RegexBuilder::ignorecase()`]
+//! (re/struct.RegexBuilder.html#method.ignorecase), [`RegexBuilder::ignoremark()`]
+//! (re/struct.RegexBuilder.html#method.ignoremark) and [`RegexBuilder::sigspace()`]
+//! (re/struct.RegexBuilder.html#method.sigspace) apply to the whole regex.
+//!
 //! # Comments
 //!
 //! Comments are allowed inside a regex.
@@ -126,8 +334,11 @@
 //! ```
 
 #[doc(hidden)] pub mod collapse;
+#[doc(hidden)] pub mod compile;
 #[doc(hidden)] pub mod parse;
 #[doc(hidden)] pub mod range_set;
+#[doc(hidden)] pub mod trie;
 #[doc(hidden)] pub mod unicode;
+#[doc(hidden)] pub mod vm;
 
 pub mod re;
@@ -1,47 +1,126 @@
+use parse::Choice::*;
+use parse::Look::*;
 use parse::Membership::*;
 use parse::Faction::*;
+use parse::Polarity::*;
 use range_set::{Range, Set};
 use std::collections::VecDeque;
 use std::{char, fmt};
+use std::cmp::Ordering;
 use std::convert::From;
-use std::result;
+use std::{mem, result};
+use std::iter::Peekable;
+use std::str::CharIndices;
 // Unicode tables for character classes are defined in libunicode
-use unicode::regex::{PERLD, PERLS, PERLW};
+use unicode;
+use unicode::case_folding::{C_plus_S_table, F_table};
+use unicode::decomposition::Canonical_table;
+use unicode::derived_property::{Alphabetic_table, Lowercase_table, Uppercase_table};
+use unicode::general_category::{Cc_table, Cn_table, M_table, Nd_table, P_table, S_table};
+use unicode::property::White_Space_table;
+use unicode::regex::{PERLD, PERLS, PERLW, UNICODE_CLASSES};
+
+// The whitespace which breaks lines. The rest of `White_Space` is horizontal.
+const VERTICAL: &'static [(char, char)] = &[
+    ('\u{a}', '\u{d}'), ('\u{85}', '\u{85}'), ('\u{2028}', '\u{2029}')
+];
 
 pub type Result<T> = result::Result<T, ParseError>;
 
 #[derive(Debug)]
 enum ParseError {
+    AdverbUnknown(String),
+    AlternationEmpty,
+    CaptureMustBind(String),
+    CaptureNameInvalid,
     ClassInvalid(char),
     ClassMustClose,
+    ClassNameNotSet(String),
+    ClassOperandMissing,
+    ClassOpMissing,
     ClassSetMustClose,
+    EllipsisBackwards(char, char),
     EllipsisCloseNeedsEscape,
     EllipsisNotFirst,
     EllipsisNotLast,
     EllipsisOnlyChar,
     EmptyRegex,
+    EscapeCodepointInvalid(String),
+    EscapeControlInvalid(char),
+    EscapeMustClose(char),
+    EscapeNameUnknown(String),
     EscapeNotLast,
+    EscapeUnknown(char),
+    GroupEmpty(char),
+    GroupMustClose(char),
     Invalid(char),
     LiteralMustClose(char),
+    LookaroundEmpty(String),
+    LookaroundInvalid(String),
+    NameUnknown(String),
+    PropertyUnknown(String),
+    QuantifierNoAtom(char),
+    RepeatCountInvalid,
+    RepeatRangeBackwards(usize, usize),
 }
 
 impl fmt::Display for ParseError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "Error: {}", match *self {
+            ParseError::AdverbUnknown(ref name) => format!("`:{}` is not a known adverb.", name),
+            ParseError::AlternationEmpty  => "A `|` or `||` cannot have an empty branch.".to_owned(),
+            ParseError::CaptureMustBind(ref name) =>
+                format!("`$<{}>` must be followed by `=` and something to capture.", name),
+            ParseError::CaptureNameInvalid =>
+                "A capture name must be an identifier closed by `>` like `$<year>`.".to_owned(),
             ParseError::ClassInvalid(ref c) =>
                 format!("`{}` is invalid inside `<>` and outside `[]`.", c),
             ParseError::ClassMustClose    => "A `<` must have a closing `>`.".to_owned(),
+            ParseError::ClassNameNotSet(ref name) =>
+                format!("`{}` can match more than one char so it can't be part of a class.", name),
+            ParseError::ClassOperandMissing =>
+                "Two ops inside `<>` like `+ +` must have a set between them.".to_owned(),
+            ParseError::ClassOpMissing    =>
+                "Two sets inside `<>` like `[a] [b]` must be joined by an op like `+`.".to_owned(),
             ParseError::ClassSetMustClose => "A `[` must have a closing `]`.".to_owned(),
+            ParseError::EllipsisBackwards(ref a, ref b) =>
+                format!("The `..` range `{:?}..{:?}` starts after its end.", a, b),
             ParseError::EllipsisCloseNeedsEscape =>
                 "An `..` cannot be closed by an unescaped `]`".to_owned(),
             ParseError::EllipsisNotFirst  => "`..` cannot be the first element in a character class.".to_owned(),
             ParseError::EllipsisNotLast   => "An `..` must be followed by another char.".to_owned(),
-            ParseError::EllipsisOnlyChar  => "`..` only operate on characters.".to_owned(),
+            ParseError::EllipsisOnlyChar  =>
+                "`..` only operate on single chars like `a` or `\\t`, not sets like `\\d`.".to_owned(),
             ParseError::EmptyRegex        => "An empty regex is not allowed.".to_owned(),
+            ParseError::EscapeCodepointInvalid(ref digits) =>
+                format!("`{}` is not a valid codepoint.", digits),
+            ParseError::EscapeControlInvalid(ref c) =>
+                format!("`\\c{}` is not a control char. Try `\\c@` through `\\c_` or `\\c?`.", c),
+            ParseError::EscapeMustClose(ref c) => format!("A `\\{}[` must have a closing `]`.", c),
+            ParseError::EscapeNameUnknown(ref name) => format!("`{}` is not a known char name.", name),
             ParseError::EscapeNotLast     => "A `\\` must be followed by another char.".to_owned(),
+            ParseError::EscapeUnknown(ref c) => format!("`\\{}` is not a known escape.", c),
+            ParseError::GroupEmpty(ref c) => format!("An empty group `{}` is not allowed.", c),
+            ParseError::GroupMustClose(ref c) => format!("A `{}` must have a closing `{}`.", c, match *c {
+                '[' => ']',
+                _   => ')',
+            }),
             ParseError::Invalid(ref c)    => format!("`{}` is not valid here.", c),
             ParseError::LiteralMustClose(ref c) =>
                 format!("A literal must have an opening and closing `{}`.", c),
+            ParseError::LookaroundEmpty(ref name) =>
+                format!("`<?{}>` must contain something to look for.", name),
+            ParseError::LookaroundInvalid(ref name) =>
+                format!("`{}` is not a valid assertion. Try `<?before ...>` or `<?after ...>`.", name),
+            ParseError::NameUnknown(ref name) => format!("`<{}>` is not a known name.", name),
+            ParseError::PropertyUnknown(ref name) =>
+                format!("`{}` is not a known Unicode property.", name),
+            ParseError::QuantifierNoAtom(ref c) =>
+                format!("The quantifier `{}` must follow something to quantify.", c),
+            ParseError::RepeatCountInvalid =>
+                "A `**` must be followed by a count like `3`, `2..5` or `2..*`.".to_owned(),
+            ParseError::RepeatRangeBackwards(ref min, ref max) =>
+                format!("The `**` range `{}..{}` has a minimum above its maximum.", min, max),
         })
     }
 }
@@ -58,6 +137,155 @@ impl From<&'static [(char, char)]> for Set {
     }
 }
 
+// The char whose codepoint is written as `digits` in `radix`.
+fn codepoint(digits: &str, radix: u32) -> Result<char> {
+    let valid = !digits.is_empty() && digits.chars().all(|c| c.is_digit(radix));
+
+    let c = if valid { u32::from_str_radix(digits, radix).ok().and_then(char::from_u32) }
+            else { None };
+
+    c.ok_or_else(|| ParseError::EscapeCodepointInvalid(digits.to_owned()))
+}
+
+// Whitespace which doesn't break lines like spaces and tabs.
+fn horizontal() -> Set {
+    Set::from(White_Space_table).difference(&VERTICAL.into())
+}
+
+const ASCII: &'static [(char, char)] = &[('\u{0}', '\u{7f}')];
+const XDIGIT: &'static [(char, char)] = &[('0', '9'), ('A', 'F'), ('a', 'f')];
+
+// The built in named classes which match a single char like `<alpha>`. These
+// follow the compatibility properties of UTS #18 except where Perl 6 differs.
+fn named_set(name: &str) -> Option<Ast> {
+    let set = match name {
+        // Perl 6 counts `_` as alphabetic.
+        "alpha"  => Set::from(Alphabetic_table).union(&'_'.into()),
+        "alnum"  => Set::from(Alphabetic_table).union(&'_'.into()).union(&Nd_table.into()),
+        "blank"  => horizontal(),
+        "cntrl"  => Cc_table.into(),
+        "digit"  => Nd_table.into(),
+        "lower"  => Lowercase_table.into(),
+        // Symbols only count as punctuation inside ASCII.
+        "punct"  => Set::from(P_table).union(&Set::from(S_table).intersection(&ASCII.into())),
+        "space"  => White_Space_table.into(),
+        "upper"  => Uppercase_table.into(),
+        "xdigit" => XDIGIT.into(),
+        // Everything but whitespace, controls and unassigned chars.
+        "graph"  => {
+            let set = Set::from(White_Space_table).union(&Cc_table.into()).union(&Cn_table.into());
+            return Some(Ast::Set(set, Exclusive))
+        },
+        // Like `graph` but horizontal whitespace is allowed.
+        "print"  => {
+            let set = vertical().union(&Cc_table.into()).union(&Cn_table.into());
+            return Some(Ast::Set(set, Exclusive))
+        },
+        _ => return None,
+    };
+
+    Some(Ast::Set(set, Inclusive))
+}
+
+// The long names of each general category.
+const GENERAL_CATEGORIES: &'static [(&'static str, &'static str)] = &[
+    ("Other", "C"), ("Control", "Cc"), ("Format", "Cf"), ("Unassigned", "Cn"),
+    ("Private_Use", "Co"), ("Letter", "L"), ("Cased_Letter", "LC"),
+    ("Lowercase_Letter", "Ll"), ("Modifier_Letter", "Lm"), ("Other_Letter", "Lo"),
+    ("Titlecase_Letter", "Lt"), ("Uppercase_Letter", "Lu"), ("Mark", "M"),
+    ("Spacing_Mark", "Mc"), ("Enclosing_Mark", "Me"), ("Nonspacing_Mark", "Mn"),
+    ("Number", "N"), ("Decimal_Number", "Nd"), ("Letter_Number", "Nl"),
+    ("Other_Number", "No"), ("Punctuation", "P"), ("Connector_Punctuation", "Pc"),
+    ("Dash_Punctuation", "Pd"), ("Close_Punctuation", "Pe"), ("Final_Punctuation", "Pf"),
+    ("Initial_Punctuation", "Pi"), ("Other_Punctuation", "Po"), ("Open_Punctuation", "Ps"),
+    ("Symbol", "S"), ("Currency_Symbol", "Sc"), ("Modifier_Symbol", "Sk"),
+    ("Math_Symbol", "Sm"), ("Other_Symbol", "So"), ("Separator", "Z"),
+    ("Line_Separator", "Zl"), ("Paragraph_Separator", "Zp"), ("Space_Separator", "Zs"),
+];
+
+// The binary properties in `UNICODE_CLASSES`. Everything else there is a
+// general category or a script.
+const BINARY_PROPERTIES: &'static [&'static str] = &[
+    "Alphabetic", "Default_Ignorable_Code_Point", "Grapheme_Extend", "Join_Control",
+    "Lowercase", "Noncharacter_Code_Point", "Uppercase", "White_Space", "XID_Continue",
+    "XID_Start",
+];
+
+// Which properties a name in `<:Name>` may refer to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Property {
+    Any,             // `<:Lu>`
+    GeneralCategory, // `<:General_Category<Lu>>` or `<:gc<Lu>>`
+    Script,          // `<:Script<Greek>>` or `<:sc<Greek>>`
+}
+
+// Property names are matched loosely following UAX44-LM3. Case, whitespace,
+// `_` and `-` are ignored as is an initial `is`.
+fn loose(name: &str) -> String {
+    let name: String = name.chars()
+                           .filter(|&c| !c.is_whitespace() && c != '_' && c != '-')
+                           .flat_map(char::to_lowercase)
+                           .collect();
+
+    if name.starts_with("is") { name[2..].to_owned() } else { name }
+}
+
+// Find the table for a property value like `Lu`, `Letter` or `Greek`.
+fn property(name: &str, kind: Property) -> Option<&'static [(char, char)]> {
+    let name = loose(name);
+    // Every general category is in `UNICODE_CLASSES` by its short name.
+    let category = GENERAL_CATEGORIES.iter()
+                                     .find(|&&(long, short)| {
+                                         loose(long) == name || loose(short) == name
+                                     })
+                                     .map(|&(_, short)| short);
+
+    UNICODE_CLASSES.iter()
+                   .find(|&&(n, _)| match kind {
+                       Property::Any => Some(n) == category || loose(n) == name,
+                       Property::GeneralCategory => Some(n) == category,
+                       Property::Script => {
+                           loose(n) == name &&
+                           !BINARY_PROPERTIES.contains(&n) &&
+                           !GENERAL_CATEGORIES.iter().any(|&(_, short)| short == n)
+                       },
+                   })
+                   .map(|&(_, table)| table)
+}
+
+// The built in named rules which can match more than one char.
+fn named_rule(name: &str) -> Option<Ast> {
+    let word = Ast::Set(PERLW.into(), Inclusive);
+
+    match name {
+        // `<.alpha> \w*`
+        "ident" => {
+            let alpha = named_set("alpha").unwrap();
+
+            Some(Ast::Group(vec![alpha, Ast::Repeat(Box::new(word), 0, None)], NonCapture))
+        },
+        // Perl 6 defines this as `<!ww> \s*` which is whitespace or anywhere
+        // that isn't between two word chars.
+        "ws"    => {
+            let space = Ast::Repeat(Box::new(Ast::Set(PERLS.into(), Inclusive)), 1, None);
+            let branches = vec![vec![space],
+                                vec![Ast::Lookaround(vec![word.clone()], Behind, Negative)],
+                                vec![Ast::Lookaround(vec![word], Ahead, Negative)]];
+
+            Some(Ast::Group(vec![Ast::Alternation(branches, Sequential)], NonCapture))
+        },
+        _ => None,
+    }
+}
+
+fn vertical() -> Set {
+    Set::from(White_Space_table).intersection(&VERTICAL.into())
+}
+
+fn is_vertical(c: char) -> bool {
+    VERTICAL.iter().any(|&(open, close)| open <= c && c <= close)
+}
+
 impl From<char> for Set {
     fn from(c: char) -> Self {
         let mut set = Set::new();
@@ -87,21 +315,19 @@ impl From<Vec<Ast>> for Set {
 
 impl From<Vec<Ast>> for Ast {
     fn from(vec: Vec<Ast>) -> Self {
-        let (mut inclusive, mut exclusive) = (Set::new(), Set::new());
+        let (mut inclusive, mut sets) = (Set::new(), vec![]);
 
         for ast in vec {
             match ast {
                 Ast::Char(c) => inclusive.insert(Range(c, c)),
                 Ast::Range(range) => inclusive.insert(range),
-                Ast::Set(set, Inclusive) => inclusive = inclusive.union(&set),
-                Ast::Set(set, Exclusive) => exclusive = exclusive.union(&set),
+                set @ Ast::Set(..) => sets.push(set),
                 _ => unreachable!(),
             }
         }
 
-        if exclusive.is_empty() { Ast::Set(inclusive, Inclusive) }
-        else { Op::Union.apply(Ast::Set(inclusive, Inclusive),
-                               Ast::Set(exclusive, Exclusive)) }
+        sets.into_iter()
+            .fold(Ast::Set(inclusive, Inclusive), |left, right| Op::Union.apply(left, right))
     }
 }
 
@@ -110,9 +336,23 @@ pub trait NextPrev {
     fn prev(&self) -> Self;
 }
 
+// Surrogates aren't chars so the chars on either side of them are neighbors.
+// There is nothing past either end so those stay put.
 impl NextPrev for char {
-    fn next(&self) -> Self { char::from_u32(*self as u32 + 1).unwrap() }
-    fn prev(&self) -> Self { char::from_u32(*self as u32 - 1).unwrap() }
+    fn next(&self) -> Self {
+        match *self {
+            '\u{d7ff}' => '\u{e000}',
+            char::MAX  => char::MAX,
+            c => char::from_u32(c as u32 + 1).unwrap(),
+        }
+    }
+    fn prev(&self) -> Self {
+        match *self {
+            '\u{e000}' => '\u{d7ff}',
+            '\u{0}'    => '\u{0}',
+            c => char::from_u32(c as u32 - 1).unwrap(),
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq, PartialOrd, Ord)]
@@ -142,6 +382,10 @@ pub enum Op {
 impl Op {
     // Apply set operations. Must have `Char` removed beforehand. Subsets should be
     // removed beforehand. This will remove new subsets before exiting.
+    //
+    // An `Exclusive` set is the complement of its chars. Union handles every
+    // combination of memberships and the rest are built from it the way De
+    // Morgan's laws describe.
     pub fn apply(&self, left: Ast, right: Ast) -> Ast {
 
         match *self {
@@ -152,45 +396,43 @@ impl Op {
             _ => unimplemented!(),
         }
     }
-    // Apply set difference.
+    // How tightly the op binds inside a class. `&` binds tighter than the
+    // others, which all share a level and apply left to right, so
+    // `<[a..z] - [aeiou] & [a..f]>` removes only `a` and `e`.
+    pub fn precedence(&self) -> u8 {
+        match *self {
+            Op::Intersection => 2,
+            _                => 1,
+        }
+    }
+    // Apply set difference. `a - b` is `a & -b`.
     fn difference(&self, left: Ast, right: Ast) -> Ast {
         match (left, right) {
             (Ast::Empty, right) => right.negate(),
             (left, Ast::Empty)  => left,
-            (Ast::Set(lset, lmem), Ast::Set(rset, rmem)) => {
-                match (lmem, rmem) {
-                    (l, r) if l == r => Ast::Set(lset.difference(&rset), l),
-                    _ => unimplemented!(),
-                }
-            },
-            _ => unimplemented!(),
+            (left, right)       => self.intersection(left, right.negate()),
         }
     }
-    // Apply symmetric set difference.
+    // Apply symmetric set difference. Complementing either side complements
+    // the result.
     fn symmetric_difference(&self, left: Ast, right: Ast) -> Ast {
         match (left, right) {
             (Ast::Empty, right) => right,
             (left, Ast::Empty)  => left,
             (Ast::Set(lset, lmembership), Ast::Set(rset, rmembership)) => {
-                if lmembership == rmembership {
-                    Ast::Set(lset.symmetric_difference(&rset), lmembership)
-                } else { unimplemented!() }
+                let membership = if lmembership == rmembership { Inclusive } else { Exclusive };
+
+                Ast::Set(lset.symmetric_difference(&rset), membership)
             },
-            _ => unimplemented!(),
+            _ => unreachable!(),
         }
     }
-    // Apply set intersection.
+    // Apply set intersection. `a & b` is `-(-a + -b)`.
     fn intersection(&self, left: Ast, right: Ast) -> Ast {
         match (left, right) {
             (Ast::Empty, _) |
             (_, Ast::Empty)  => Ast::Empty,
-            (Ast::Set(lset, lmem), Ast::Set(rset, rmem)) => {
-                match (lmem, rmem) {
-                    (l, r) if l == r => Ast::Set(lset.intersection(&rset), l),
-                    _ => unimplemented!(),
-                }
-            },
-            _ => unimplemented!(),
+            (left, right)    => self.union(left.negate(), right.negate()).negate(),
         }
     }
     // Apply set union.
@@ -202,11 +444,13 @@ impl Op {
                 // Unifying sets with opposite membership isn't obvious. If
                 // -3 is 3 Exclusive and 3 is Inclusive then `-3 + 3` is a
                 // union which is identical to `-(3 - 3)` = `-()`. Similarly,
-                // `-1 + 7` = `-(1 - 7)` = `-1`.
+                // `-1 + 7` = `-(1 - 7)` = `-1`. Two complements only leave out
+                // what both leave out so `-1 + -12` = `-(1 & 12)` = `-1`.
                 match (lmembership, rmembership) {
-                    (x, y) if x == y   => Ast::Set(lset.union(&rset), x),
-                    (x @ Exclusive, _) => Ast::Set(lset.difference(&rset), x),
-                    (Inclusive, y)     => Ast::Set(rset.difference(&lset), y),
+                    (Inclusive, Inclusive) => Ast::Set(lset.union(&rset), Inclusive),
+                    (Exclusive, Exclusive) => Ast::Set(lset.intersection(&rset), Exclusive),
+                    (Exclusive, Inclusive) => Ast::Set(lset.difference(&rset), Exclusive),
+                    (Inclusive, Exclusive) => Ast::Set(rset.difference(&lset), Exclusive),
                 }
             },
             _ => unreachable!(),
@@ -214,12 +458,109 @@ impl Op {
     }
 }
 
+// A zero width assertion about where in the text the match is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Anchor {
+    EndOfLine,     // `$$`
+    EndOfString,   // `$`
+    StartOfLine,   // `^^`
+    StartOfString, // `^`
+    WordBoundary,  // `<wb>`
+    WordEnd,       // `>>` or `»`
+    WordStart,     // `<<` or `«`
+}
+
+// A word char is anything `\w` matches.
+fn is_word(c: char) -> bool { in_table(PERLW, c) }
+
+fn is_mark(c: char) -> bool { in_table(M_table, c) }
+
+fn in_table(table: &[(char, char)], c: char) -> bool {
+    table.binary_search_by(|&(open, close)| {
+        if close < c { Ordering::Less } else if open > c { Ordering::Greater } else { Ordering::Equal }
+    }).is_ok()
+}
+
+impl Anchor {
+    // True if the anchor holds at byte offset `pos` of `txt`. Lines end at
+    // the same newlines `\n` matches. A newline which ends the text doesn't
+    // start another line.
+    pub fn is_match(&self, txt: &str, pos: usize) -> bool {
+        let (before, after) = txt.split_at(pos);
+        let word_before = before.chars().next_back().map_or(false, is_word);
+        let word_after = after.chars().next().map_or(false, is_word);
+        let newline_before = before.ends_with(is_vertical);
+        // `\r\n` is a single newline so a line never starts or ends inside it.
+        let in_crlf = before.ends_with('\r') && after.starts_with('\n');
+
+        match *self {
+            Anchor::EndOfLine => {
+                !in_crlf && (after.starts_with(is_vertical) || (after.is_empty() && !newline_before))
+            },
+            Anchor::EndOfString => after.is_empty(),
+            Anchor::StartOfLine => {
+                !in_crlf && (before.is_empty() || (newline_before && !after.is_empty()))
+            },
+            Anchor::StartOfString => before.is_empty(),
+            Anchor::WordBoundary  => word_before != word_after,
+            Anchor::WordEnd       => word_before && !word_after,
+            Anchor::WordStart     => !word_before && word_after,
+        }
+    }
+    // Return the first byte offset at or after `from` where the anchor holds.
+    pub fn find(&self, txt: &str, from: usize) -> Option<usize> {
+        match *self {
+            Anchor::EndOfString => Some(txt.len()),
+            Anchor::StartOfString => if from == 0 { Some(0) } else { None },
+            // Lines only start or end next to a newline or at either end.
+            Anchor::EndOfLine | Anchor::StartOfLine => {
+                let newlines = txt[from..].char_indices()
+                                          .filter(|&(_, c)| is_vertical(c))
+                                          .flat_map(|(i, c)| vec![from + i, from + i + c.len_utf8()]);
+
+                Some(from).into_iter()
+                          .chain(newlines)
+                          .chain(Some(txt.len()))
+                          .find(|&pos| pos >= from && self.is_match(txt, pos))
+            },
+            // Word boundaries could be between any two chars.
+            Anchor::WordBoundary | Anchor::WordEnd | Anchor::WordStart => {
+                txt[from..].char_indices()
+                           .map(|(i, _)| from + i)
+                           .chain(Some(txt.len()))
+                           .find(|&pos| self.is_match(txt, pos))
+            },
+        }
+    }
+}
+
+// How an alternation picks between branches which could all match.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Choice {
+    Longest,    // `|` tries the branch with the longest declarative prefix first
+    Sequential, // `||` tries branches in order
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Faction {
     Capture,
     NonCapture,
 }
 
+// Which way a lookaround looks from the current position.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Look {
+    Ahead,  // `<?after foo>`
+    Behind, // `<?before foo>`
+}
+
+// Whether a zero width assertion must match or must not match.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Polarity {
+    Negative, // `<!...>`
+    Positive, // `<?...>`
+}
+
 trait SetMatch {
     fn find_set(&self, &Set, &Membership) -> Option<usize>;
     fn starts_with_set(&self, &Set, &Membership) -> bool;
@@ -227,11 +568,12 @@ trait SetMatch {
 
 impl SetMatch for str {
     fn find_set(&self, set: &Set, membership: &Membership) -> Option<usize> {
-        self.chars()
-            .position(|c| match *membership {
+        self.char_indices()
+            .find(|&(_, c)| match *membership {
                 Inclusive =>  set.contains(c),
                 Exclusive => !set.contains(c),
             })
+            .map(|(pos, _)| pos)
     }
     fn starts_with_set(&self, set: &Set, membership: &Membership) -> bool {
         self.chars()
@@ -247,12 +589,23 @@ impl SetMatch for str {
 #[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
 pub enum Ast {
     Empty,
+    Alternation(Vec<Vec<Ast>>, Choice), // a | b or a || b
+    Anchor(Anchor),                 // ^ $ ^^ $$
     Char(char),                     // abc123
     Class(VecDeque<Ast>),           // <[135] + [68\w]>
     Dot,                            // .
+    // Matches text which is the same as the literal once it's been folded.
+    Folded(String, Fold),           // `a` or `'abc'` after a `:i`
     Group(Vec<Ast>, Faction),       // [123] or (123) outside a `<>`
     Literal(String),                // `'hello'` or `"hello"`
+    // `\r\n` or any single vertical whitespace char.
+    Newline,                        // \n outside a `<>`
+    Lookaround(Vec<Ast>, Look, Polarity), // <?after foo> or <!before bar>
+    NamedCapture(String, Box<Ast>), // $<year>=[\d**4] or $<year>=(\d**4)
     Op(Op),
+    // A quantified atom with the minimum and (if bounded) maximum number of
+    // repetitions.
+    Repeat(Box<Ast>, usize, Option<usize>), // a*, a+, a?, a ** 2..5
     // Unicode uses (open, close) pairs to denote range. A set of these is
     // more efficient than specifying every character. A set may include a
     // few dozen pairs instead of 100s/1000s.
@@ -267,39 +620,414 @@ impl Ast {
         match self {
             &Ast::Char(c) => txt.find(c),
             &Ast::Literal(ref s) => txt.find(s),
+            // A match doesn't start on a mark which belongs to the char before it.
+            &Ast::Folded(_, fold) => {
+                txt.char_indices()
+                   .filter(|&(_, c)| !fold.ignores(c))
+                   .map(|(i, _)| i)
+                   .find(|&i| self.trim_left_match(&txt[i..]).is_some())
+            },
+            // Any char but there must be one.
+            &Ast::Dot => if txt.is_empty() { None } else { Some(0) },
+            // A `\r\n` starts with a vertical char too.
+            &Ast::Newline => txt.find(is_vertical),
             &Ast::Set(ref set, ref membership) => txt.find_set(set, membership),
+            &Ast::Group(ref vec, _) => vec.first().map_or(Some(0), |ast| ast.find(txt)),
+            &Ast::NamedCapture(_, ref ast) => ast.find(txt),
+            // Zero width so it could match anywhere. `MatchStart` checks a
+            // leading anchor itself since it needs to see the text before `txt`.
+            &Ast::Anchor(_) |
+            &Ast::Lookaround(..) => Some(0),
+            // Any branch could be the one which matches.
+            &Ast::Alternation(ref branches, _) => {
+                branches.iter()
+                        .filter_map(|branch| branch.first().map_or(Some(0), |ast| ast.find(txt)))
+                        .min()
+            },
+            // Zero repetitions match anywhere.
+            &Ast::Repeat(_, 0, _) => Some(0),
+            &Ast::Repeat(ref ast, _, _) => ast.find(txt),
             _ => unimplemented!(),
         }
     }
     // If the str matches, return the remainer of the str after the 1st match
-    // has been trimmed off.
+    // has been trimmed off. Only single atoms are handled here. Anything
+    // which can match more than one way is left to the `vm`.
     pub fn trim_left_match<'a>(&self, txt: &'a str) -> Option<&'a str> {
         match self {
-            &Ast::Char(c)        => if txt.starts_with(c) { Some(&txt[1..]) } else { None },
+            &Ast::Char(c)        => if txt.starts_with(c) { Some(&txt[c.len_utf8()..]) } else { None },
             &Ast::Literal(ref s) => if txt.starts_with(s) { Some(&txt[s.len()..]) } else { None },
+            &Ast::Folded(ref s, fold) => {
+                if s.is_empty() { return Some(txt) }
+                let mut chars = s.chars();
+
+                // The folded text must line up with the literal char for char.
+                for (c, end) in fold.chars(txt) {
+                    if chars.next() != Some(c) { return None }
+                    if chars.as_str().is_empty() { return end.map(|end| &txt[end..]) }
+                }
+
+                None
+            },
+            &Ast::Dot            => if txt.is_empty() { None } else { Some(trim_char(txt)) },
+            // `\r\n` is a single newline so it's never split.
+            &Ast::Newline        => {
+                if txt.starts_with("\r\n") { Some(&txt[2..]) }
+                else if txt.starts_with(is_vertical) { Some(trim_char(txt)) }
+                else { None }
+            },
             &Ast::Set(ref set, ref membership) => {
-                if txt.starts_with_set(set, membership) { Some(&txt[1..]) } else { None }
+                if txt.starts_with_set(set, membership) { Some(trim_char(txt)) } else { None }
             },
             _ => unimplemented!(),
         }
     }
+    // Like `trim_left_match` but the match has to end where `txt` does.
+    // Returns what's left before it for every way it could match, nearest
+    // first. A lookbehind is matched backwards with these.
+    pub fn trim_right_matches<'a>(&self, txt: &'a str) -> Vec<&'a str> {
+        match self {
+            &Ast::Char(c) => {
+                if txt.ends_with(c) { vec![&txt[..txt.len() - c.len_utf8()]] } else { vec![] }
+            },
+            &Ast::Literal(ref s) => {
+                if txt.ends_with(s) { vec![&txt[..txt.len() - s.len()]] } else { vec![] }
+            },
+            _ => {
+                // How many chars back it could start. An ignored mark always
+                // goes with the char before it so it's never a start.
+                let (most, fold) = match self {
+                    &Ast::Folded(ref s, fold) => (s.chars().count(), fold),
+                    &Ast::Newline => (2, Fold::default()),
+                    _ => (1, Fold::default()),
+                };
+                let starts = txt.char_indices()
+                                .rev()
+                                .filter(|&(_, c)| !fold.ignores(c))
+                                .map(|(i, _)| i)
+                                .take(most);
+
+                Some(txt.len()).into_iter()
+                               .chain(starts)
+                               .filter(|&i| self.trim_left_match(&txt[i..]) == Some(""))
+                               .map(|i| &txt[..i])
+                               .collect()
+            },
+        }
+    }
     fn negate(self) -> Self {
         match self {
             Ast::Set(set, membership) => Ast::Set(set, membership.negate()),
             _ => unreachable!(),
         }
     }
+    // Fold what a single atom matches. A group was already folded while its
+    // insides were parsed so it's left alone.
+    fn fold(self, fold: Fold) -> Self {
+        match self {
+            Ast::Char(c) => Ast::Folded(fold.string(&c.to_string()), fold),
+            Ast::Literal(s) => Ast::Folded(fold.string(&s), fold),
+            // A class matches a single char. Without its marks the marks after
+            // that char go along with it the way they do in a literal.
+            Ast::Set(..) |
+            Ast::Class(_) if fold.marks => {
+                let marks = Ast::Repeat(Box::new(Ast::Set(ignorable(), Inclusive)), 0, None);
+
+                Ast::Group(vec![self.fold_class(fold), marks], NonCapture)
+            },
+            Ast::Set(..) |
+            Ast::Class(_) => self.fold_class(fold),
+            // A `< big small >` word list.
+            Ast::Alternation(branches, choice) => {
+                let branches = branches.into_iter()
+                                       .map(|branch| {
+                                           branch.into_iter().map(|ast| ast.fold(fold)).collect()
+                                       })
+                                       .collect();
+
+                Ast::Alternation(branches, choice)
+            },
+            Ast::NamedCapture(name, ast) => Ast::NamedCapture(name, Box::new(ast.fold(fold))),
+            ast => ast,
+        }
+    }
+    // Fold the sets of a class including those nested inside of it.
+    fn fold_class(self, fold: Fold) -> Self {
+        match self {
+            Ast::Set(set, membership) => Ast::Set(fold.set(&set), membership),
+            Ast::Class(deque) => {
+                Ast::Class(deque.into_iter().map(|ast| ast.fold_class(fold)).collect())
+            },
+            ast => ast,
+        }
+    }
+}
+
+// What is ignored when comparing text with a `Folded` literal. Both are
+// folded the same way first.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, PartialOrd, Ord)]
+pub struct Fold {
+    pub case: bool,  // `:i`
+    pub marks: bool, // `:m`
+}
+
+impl Fold {
+    // Fold `txt` one char at a time.
+    pub fn chars<'a>(&self, txt: &'a str) -> FoldChars<'a> {
+        FoldChars { fold: *self,
+                    chars: txt.char_indices().peekable(),
+                    folded: String::new(),
+                    at: 0,
+                    end: 0,
+        }
+    }
+    // Push what `c` folds to onto `out`. A few chars like `ß` fold to more
+    // than one. Without its marks a char is decomposed first so `é` is `e`
+    // and a lone mark folds to nothing.
+    fn push(&self, c: char, out: &mut String) {
+        if !self.marks { return self.push_case(c, out) }
+
+        match decompose(c) {
+            Some(s) => for c in s.chars().filter(|&c| !is_mark(c)) { self.push_case(c, out) },
+            None    => if !is_mark(c) { self.push_case(c, out) },
+        }
+    }
+    fn push_case(&self, c: char, out: &mut String) {
+        if !self.case { return out.push(c) }
+
+        match F_table.binary_search_by_key(&c, |&(from, _)| from) {
+            Ok(i)  => out.push_str(F_table[i].1),
+            Err(_) => out.push(fold_case(c)),
+        }
+    }
+    // True if `c` folds to nothing at all.
+    fn ignores(&self, c: char) -> bool {
+        self.marks && decompose(c).map_or(is_mark(c), |s| s.chars().all(is_mark))
+    }
+    fn string(&self, s: &str) -> String {
+        let mut out = String::new();
+        for c in s.chars() { self.push(c, &mut out) }
+
+        out
+    }
+    // A set can't be folded so every char which would fold to the same
+    // thing as one of its chars is added instead.
+    pub fn set(&self, set: &Set) -> Set {
+        let set = if self.marks { mark_closure(set) } else { set.clone() };
+
+        if self.case { case_closure(&set) } else { set }
+    }
+    // Every char whose folding could start with a char in `set`. Unlike a
+    // class, a folded literal like `'ss'` can match the `ss` which `ß` folds to.
+    pub fn starts(&self, set: &Set) -> Set {
+        let mut starts = self.set(set);
+
+        if self.case {
+            for &(from, to) in F_table {
+                let first = to.chars().next().unwrap();
+                if set.contains(first) { starts.insert(Range(from, from)) }
+            }
+        }
+
+        starts
+    }
+}
+
+// Each folded char comes with the byte offset in the text just past the char
+// it came from. When a char folds to more than one, only the last of them
+// has an offset since a match can't end partway through the char. Chars
+// which fold to nothing, like the marks after an `e`, belong to the char
+// before them so a match which ends there takes them along.
+pub struct FoldChars<'a> {
+    fold: Fold,
+    chars: Peekable<CharIndices<'a>>,
+    // What the current char folded to and how much of it has been returned.
+    folded: String,
+    at: usize,
+    // The byte offset just past the current char.
+    end: usize,
+}
+
+impl<'a> Iterator for FoldChars<'a> {
+    type Item = (char, Option<usize>);
+
+    fn next(&mut self) -> Option<(char, Option<usize>)> {
+        while self.at == self.folded.len() {
+            let (i, c) = match self.chars.next() {
+                Some(next) => next,
+                None => return None,
+            };
+
+            self.folded.clear();
+            self.fold.push(c, &mut self.folded);
+            self.at = 0;
+            self.end = i + c.len_utf8();
+
+            while let Some(&(i, c)) = self.chars.peek() {
+                if self.folded.is_empty() || !self.fold.ignores(c) { break }
+
+                self.chars.next();
+                self.end = i + c.len_utf8();
+            }
+        }
+
+        let c = self.folded[self.at..].chars().next().unwrap();
+        self.at += c.len_utf8();
+
+        Some((c, if self.at == self.folded.len() { Some(self.end) } else { None }))
+    }
+}
+
+// The simple case folding of `c`. Chars without one fold to themselves.
+pub fn fold_case(c: char) -> char {
+    match C_plus_S_table.binary_search_by_key(&c, |&(from, _)| from) {
+        Ok(i)  => C_plus_S_table[i].1,
+        Err(_) => c,
+    }
+}
+
+// The canonical decomposition of `c` if it has one.
+fn decompose(c: char) -> Option<&'static str> {
+    Canonical_table.binary_search_by_key(&c, |&(from, _)| from)
+                   .ok()
+                   .map(|i| Canonical_table[i].1)
+}
+
+// Every char which is a char in `set` once its marks are gone, like `é` for
+// an `e`.
+fn mark_closure(set: &Set) -> Set {
+    let mut closure = set.clone();
+
+    for &(from, to) in Canonical_table {
+        let mut base = to.chars().filter(|&c| !is_mark(c));
+
+        match (base.next(), base.next()) {
+            (Some(c), None) if set.contains(c) => closure.insert(Range(from, from)),
+            _ => {},
+        }
+    }
+
+    closure
+}
+
+// Every char which folds to nothing once marks are dropped.
+fn ignorable() -> Set {
+    let mut set = Set::from(M_table);
+
+    for &(from, to) in Canonical_table {
+        if to.chars().all(is_mark) { set.insert(Range(from, from)) }
+    }
+
+    set
+}
+
+// Every char which folds to the same char as something in `set` does. What
+// `set` folds to is added first, then everything which folds to one of those.
+fn case_closure(set: &Set) -> Set {
+    let mut closure = set.clone();
+
+    for &(from, to) in C_plus_S_table {
+        if set.contains(from) { closure.insert(Range(to, to)) }
+    }
+
+    let folded = closure.clone();
+    for &(from, to) in C_plus_S_table {
+        if folded.contains(to) { closure.insert(Range(from, from)) }
+    }
+
+    closure
+}
+
+// Collects the branches of `a | b || c` while parsing. `||` binds looser
+// than `|` so `a | b || c` is `[a | b] || c`.
+struct Branches {
+    longest: Vec<Vec<Ast>>,
+    sequential: Vec<Vec<Ast>>,
+}
+
+impl Branches {
+    fn new() -> Self { Branches { longest: vec![], sequential: vec![] } }
+    fn is_empty(&self) -> bool { self.longest.is_empty() && self.sequential.is_empty() }
+    // Add a branch which was ended by `|` or `||`.
+    fn push(&mut self, branch: Vec<Ast>, choice: Choice) -> Result<()> {
+        if branch.iter().all(|ast| *ast == Ast::Empty) {
+            // A leading `|` or `||` is allowed and ignored.
+            return if self.is_empty() { Ok(()) } else { Err(ParseError::AlternationEmpty) }
+        }
+
+        self.longest.push(branch);
+
+        if choice == Sequential {
+            let longest = mem::replace(&mut self.longest, vec![]);
+            self.sequential.push(Branches::join(longest, Longest));
+        }
+
+        Ok(())
+    }
+    // Add the final branch and return the whole alternation.
+    fn finish(mut self, last: Vec<Ast>) -> Result<Vec<Ast>> {
+        if self.is_empty() { return Ok(last) }
+
+        try!(self.push(last, Sequential));
+
+        Ok(Branches::join(self.sequential, Sequential))
+    }
+    // A single branch needs no alternation around it.
+    fn join(mut branches: Vec<Vec<Ast>>, choice: Choice) -> Vec<Ast> {
+        if branches.len() == 1 { branches.pop().unwrap() }
+        else { vec![Ast::Alternation(branches, choice)] }
+    }
+}
+
+// The name at the start of `chars` such as `alpha` in `alpha + [_]`.
+fn leading_name(chars: &[char]) -> String {
+    chars.iter().take_while(|&&c| c.is_alphanumeric() || c == '_').cloned().collect()
+}
+
+fn is_name(name: &str) -> bool { named_set(name).is_some() || named_rule(name).is_some() }
+
+fn trim_whitespace(chars: &[char]) -> &[char] {
+    let start = chars.iter().position(|c| !c.is_whitespace()).unwrap_or(chars.len());
+
+    &chars[start..]
+}
+
+// Trim the first char off of `txt`.
+fn trim_char(txt: &str) -> &str {
+    txt.chars()
+       .next()
+       .map_or(txt, |c| &txt[c.len_utf8()..])
+}
+
+// Settings which change how a regex is parsed.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Options {
+    // `before` and `after` mean what they do in Perl 6 instead of being
+    // swapped. See `perl_changes.md`.
+    pub raku_lookaround: bool,
+    // Match as if the whole regex started with `:i`.
+    pub ignorecase: bool,
+    // Match as if the whole regex started with `:m`.
+    pub ignoremark: bool,
+    // Match as if the whole regex started with `:s`.
+    pub sigspace: bool,
 }
 
 pub fn parse(s: &str) -> Result<Vec<Ast>> {
+    parse_with(s, Options::default())
+}
+
+pub fn parse_with(s: &str, options: Options) -> Result<Vec<Ast>> {
     Parser { chars: s.chars().collect(),
              pos: 0,
+             options,
     }.parse()
 }
 
 struct Parser {
     chars: Vec<char>,
     pos: usize,
+    options: Options,
 }
 
 impl Parser {
@@ -314,32 +1042,144 @@ impl Parser {
         }
     }
     fn parse(&mut self) -> Result<Vec<Ast>> {
-        let mut vec = vec![];
-
         if self.chars.len() == 0 { return Err(ParseError::EmptyRegex) }
 
+        self.parse_branches(None)
+    }
+    // Parse everything up to `close` or the end if there is no `close`. Stops
+    // with `close` as the current char.
+    fn parse_branches(&mut self, close: Option<char>) -> Result<Vec<Ast>> {
+        let mut vec = vec![];
+        let mut branches = Branches::new();
+        // With `:s`, whitespace after an atom matches `<.ws>`. It isn't added
+        // until something other than a quantifier follows so `a +` still
+        // repeats the `a`. Comments and adverbs aren't atoms.
+        let (mut after_atom, mut space) = (false, false);
+
         loop {
             let c = self.cur();
 
-            if c.is_alphanumeric() || c == '_' { vec.push(Ast::Char(c)) }
-            else if !c.is_whitespace() {
-                vec.push(try!(match c {
-                    '\\'       => self.parse_escape_set(),
-                    '\'' | '"' => self.parse_literal(),
-                    '<'        => self.parse_class(),
-                    '.'        => Ok(Ast::Dot),
-                    '#'        => self.parse_comment(),
-                    _          => Err(ParseError::Invalid(c)),
-                }));
+            if space && (Some(c) == close || c == '|') {
+                vec.push(named_rule("ws").unwrap());
+                space = false;
+            }
+
+            if Some(c) == close { return branches.finish(vec) }
+            else if c == '|' {
+                let choice = if self.peek('|') {
+                    self.next(); // Advance to the second `|`
+                    Sequential
+                } else { Longest };
+
+                try!(branches.push(mem::replace(&mut vec, vec![]), choice));
+                after_atom = false;
+            } else if c.is_whitespace() {
+                space = space || (self.options.sigspace && after_atom);
+            } else {
+                match c {
+                    '*' | '+' | '?' => space = false,
+                    '#' => {},
+                    _ if space => {
+                        vec.push(named_rule("ws").unwrap());
+                        space = false;
+                    },
+                    _ => {},
+                }
+
+                let ast = try!(match c {
+                    '*' | '+' | '?' => {
+                        let atom = vec.pop();
+                        self.parse_quantifier(atom)
+                    },
+                    '$' if self.peek('<') => self.parse_named_capture(),
+                    _   => self.parse_atom(),
+                });
+
+                if ast != Ast::Empty { after_atom = true }
+
+                let fold = Fold { case: self.options.ignorecase, marks: self.options.ignoremark };
+                vec.push(if fold == Fold::default() { ast } else { ast.fold(fold) });
             }
 
             if !self.next() { break }
         }
 
-        Ok(vec)
+        if space { vec.push(named_rule("ws").unwrap()) }
+
+        match close {
+            Some(']') => Err(ParseError::GroupMustClose('[')),
+            Some(')') => Err(ParseError::GroupMustClose('(')),
+            Some(_)   => Err(ParseError::ClassMustClose),
+            None      => branches.finish(vec),
+        }
+    }
+    // Parse a single atom starting at the current char.
+    fn parse_atom(&mut self) -> Result<Ast> {
+        let c = self.cur();
+
+        if c.is_alphanumeric() || c == '_' { return Ok(Ast::Char(c)) }
+
+        match c {
+            // A set only holds single chars so the `\r\n` of `\n` needs an `Ast`
+            // of its own.
+            '\\' if self.peek('n') => { self.next(); Ok(Ast::Newline) },
+            '\\'       => self.parse_escape_set(),
+            '\'' | '"' => self.parse_literal(),
+            '<' if self.peek('?') || self.peek('!') => self.parse_lookaround(),
+            '<' if self.peek('<') => { self.next(); Ok(Ast::Anchor(Anchor::WordStart)) },
+            '<' if self.is_lone_name() => self.parse_name(),
+            '<' if self.is_word_list() => self.parse_words(),
+            '<'        => self.parse_class(),
+            '[' | '('  => self.parse_group(),
+            '>' if self.peek('>') => { self.next(); Ok(Ast::Anchor(Anchor::WordEnd)) },
+            '«'        => Ok(Ast::Anchor(Anchor::WordStart)),
+            '»'        => Ok(Ast::Anchor(Anchor::WordEnd)),
+            '.'        => Ok(Ast::Dot),
+            '^' if self.peek('^') => { self.next(); Ok(Ast::Anchor(Anchor::StartOfLine)) },
+            '^'        => Ok(Ast::Anchor(Anchor::StartOfString)),
+            '$' if self.peek('$') => { self.next(); Ok(Ast::Anchor(Anchor::EndOfLine)) },
+            '$'        => Ok(Ast::Anchor(Anchor::EndOfString)),
+            '#'        => self.parse_comment(),
+            ':'        => self.parse_adverb(),
+            _          => Err(ParseError::Invalid(c)),
+        }
+    }
+    // Parse the `[ a b ]` or `( a b )`. Assume `[` or `(` is the current char.
+    fn parse_group(&mut self) -> Result<Ast> {
+        let open = self.cur();
+        let (close, faction) = match open {
+            '[' => (']', NonCapture),
+            _   => (')', Capture),
+        };
+
+        if !self.next() { return Err(ParseError::GroupMustClose(open)) }
+
+        // An adverb inside the group doesn't outlast it.
+        let options = self.options;
+        let vec = try!(self.parse_branches(Some(close)));
+        self.options = options;
+        if vec.iter().all(|ast| *ast == Ast::Empty) { return Err(ParseError::GroupEmpty(open)) }
+
+        Ok(Ast::Group(vec, faction))
     }
     // Parse the `< [123 a] + [4 \d] - [\w \d] >`
     fn parse_class(&mut self) -> Result<Ast> {
+        match try!(self.parse_class_expr('>')) {
+            Some(deque) => Ok(Ast::Class(deque)),
+            None        => Err(ParseError::ClassMustClose),
+        }
+    }
+    // Inside a `<>`, parse the `( [aeiou] & [a..f] )`. Assume `(` is the current
+    // char. It becomes a nested `Class` which is collapsed on its own.
+    fn parse_class_group(&mut self) -> Result<Ast> {
+        match try!(self.parse_class_expr(')')) {
+            Some(ref deque) if deque.is_empty() => Err(ParseError::GroupEmpty('(')),
+            Some(deque) => Ok(Ast::Class(deque)),
+            None        => Err(ParseError::GroupMustClose('(')),
+        }
+    }
+    // Parse operands and operators up to `close`. `None` if `close` is never found.
+    fn parse_class_expr(&mut self, close: char) -> Result<Option<VecDeque<Ast>>> {
         // Classes will need to be merged later which requires collapsing from the
         // front so I'm using a deque (`<[abc] + [cde]>` collapses to `<[a...e]>`).
         let mut deque = VecDeque::new();
@@ -348,7 +1188,7 @@ impl Parser {
         while self.next() {
             let c = self.cur();
 
-            if c == '>' {
+            if c == close {
                 closed = true;
                 break;
             } else if !c.is_whitespace() {
@@ -358,32 +1198,49 @@ impl Parser {
                     '&'       => Ok(Ast::Op(Op::Intersection)),
                     '+' | '|' => Ok(Ast::Op(Op::Union)),
                     '['       => self.parse_class_set(),
+                    '('       => self.parse_class_group(),
+                    ':'       => self.parse_property(),
+                    // Only reachable inside `()` since `>` closes anything else.
+                    '>'       => Err(ParseError::GroupMustClose('(')),
+                    c if c.is_alphabetic() => self.parse_class_name(),
                     _         => Err(ParseError::ClassInvalid(c)),
                 }));
             }
         }
 
-        if !closed { return Err(ParseError::ClassMustClose) }
+        if !closed { return Ok(None) }
 
         // Insert `Empty` in front if first character is a binary op.
-        match deque[0] {
-            Ast::Op(Op::Difference) |
-            Ast::Op(Op::SymmetricDifference) |
-            Ast::Op(Op::Intersection) |
-            Ast::Op(Op::Union) => deque.push_front(Ast::Empty),
-            _ => {},
+        if let Some(&Ast::Op(_)) = deque.front() { deque.push_front(Ast::Empty) }
+        // Insert `Empty` in back if last character is a binary op.
+        if let Some(&Ast::Op(_)) = deque.back() { deque.push_back(Ast::Empty) }
+
+        // Collapsing relies on sets and ops taking turns starting with a set.
+        for (i, ast) in deque.iter().enumerate() {
+            match (i % 2 == 0, ast) {
+                (true, &Ast::Op(_)) => return Err(ParseError::ClassOperandMissing),
+                (false, &Ast::Op(_)) | (true, _) => {},
+                (false, _) => return Err(ParseError::ClassOpMissing),
+            }
         }
 
-        // Insert `Empty` in back if last character is a binary op.
-        match deque[deque.len()-1] {
-            Ast::Op(Op::Difference) |
-            Ast::Op(Op::SymmetricDifference) |
-            Ast::Op(Op::Intersection) |
-            Ast::Op(Op::Union) => deque.push_back(Ast::Empty),
-            _ => {},
+        Ok(Some(deque))
+    }
+    // Inside a `<>`, parse a named class like the `alpha` of `<alpha + [_-]>`.
+    // Assume the current char is the first of the name.
+    fn parse_class_name(&mut self) -> Result<Ast> {
+        let mut name = self.cur().to_string();
+
+        while self.chars.get(self.pos + 1).map_or(false, |&c| c.is_alphanumeric() || c == '_') {
+            self.next();
+            name.push(self.cur());
         }
 
-        Ok(Ast::Class(deque))
+        match named_set(&name) {
+            Some(ast) => Ok(ast),
+            None if named_rule(&name).is_some() => Err(ParseError::ClassNameNotSet(name)),
+            None => Err(ParseError::NameUnknown(name)),
+        }
     }
     // Inside a `<>`, parse the `[123 a]` or `[4 \d]`. Assume `[` is the first char.
     fn parse_class_set(&mut self) -> Result<Ast> {
@@ -423,6 +1280,27 @@ impl Parser {
 
         Ok(vec.into())
     }
+    // Parse an adverb like `:i` or `:!ignoremark`. It lasts until the end of
+    // the group it's in. Assume `:` is the current char.
+    fn parse_adverb(&mut self) -> Result<Ast> {
+        let on = !self.peek('!');
+        if !on { self.next(); } // Advance to the `!`
+        let mut name = String::new();
+
+        while self.chars.get(self.pos + 1).map_or(false, |c| c.is_alphanumeric()) {
+            self.next();
+            name.push(self.cur());
+        }
+
+        match &*name {
+            "i" | "ignorecase" => self.options.ignorecase = on,
+            "m" | "ignoremark" => self.options.ignoremark = on,
+            "s" | "sigspace"   => self.options.sigspace = on,
+            _ => return Err(ParseError::AdverbUnknown(name)),
+        }
+
+        Ok(Ast::Empty)
+    }
     // When a `#` initiates a comment, continue parsing to the end of the line
     fn parse_comment(&mut self) -> Result<Ast> {
         while self.next() {
@@ -440,39 +1318,337 @@ impl Parser {
         while self.next() {
             let b = self.cur();
             if !b.is_whitespace() {
-                return match b {
+                let b = try!(match b {
                     ']'  => Err(ParseError::EllipsisCloseNeedsEscape),
-                    '\\' => self.parse_escape().map(|c| Ast::Range(Range(a, c))),
-                    _    => Ok(Ast::Range(Range(a, b))),
-                };
+                    '\\' => self.parse_escape(),
+                    _    => Ok(b),
+                });
+
+                if a > b { return Err(ParseError::EllipsisBackwards(a, b)) }
+
+                return Ok(Ast::Range(Range(a, b)))
             }
         }
         
         Err(ParseError::EllipsisNotLast)
     }
-    // Return the next character which follows a `\`.
+    // Return the char a `\` escapes. Assume `\` is the current char.
     fn parse_escape(&mut self) -> Result<char> {
         if !self.next() { return Err(ParseError::EscapeNotLast) }
 
-        Ok(self.cur())
+        self.parse_escaped_char()
+    }
+    // The current char follows a `\`. Return the char the escape stands for.
+    // Alphanumerics are reserved for escapes so an unknown one is an error
+    // while anything else is just itself.
+    fn parse_escaped_char(&mut self) -> Result<char> {
+        match self.cur() {
+            '0' => Ok('\0'),
+            'c' => self.parse_escape_control(),
+            'e' => Ok('\u{1b}'),
+            'f' => Ok('\u{c}'),
+            'n' => Ok('\n'),
+            'o' => self.parse_escape_number(8),
+            'r' => Ok('\r'),
+            't' => Ok('\t'),
+            'x' => self.parse_escape_number(16),
+            c if c.is_alphanumeric() => Err(ParseError::EscapeUnknown(c)),
+            c => Ok(c),
+        }
+    }
+    // Collect what's inside the `[ ]` of `\x[263A]` or `\c[NAME]`. Assume `[`
+    // is the current char.
+    fn parse_escape_brackets(&mut self, letter: char) -> Result<String> {
+        let mut s = String::new();
+
+        loop {
+            if !self.next() { return Err(ParseError::EscapeMustClose(letter)) }
+            if self.cur() == ']' { return Ok(s.trim().to_owned()) }
+
+            s.push(self.cur());
+        }
+    }
+    // Parse `\c[LATIN SMALL LETTER A]`, `\c[97]`, `\c97` or a control char
+    // like `\cA`. Assume `c` is the current char.
+    fn parse_escape_control(&mut self) -> Result<char> {
+        if self.peek('[') {
+            self.next(); // Advance to the `[`
+            let name = try!(self.parse_escape_brackets('c'));
+
+            // Names never start with a digit.
+            if name.starts_with(|c: char| c.is_digit(10)) { return codepoint(&name, 10) }
+
+            return unicode::name::lookup(&name).ok_or(ParseError::EscapeNameUnknown(name))
+        }
+
+        if self.chars.get(self.pos + 1).map_or(false, |c| c.is_digit(10)) {
+            return self.parse_escape_number(10)
+        }
+
+        if !self.next() { return Err(ParseError::EscapeNotLast) }
+
+        // `\c@` is 0 and `\cA` is 1 up through `\c_`. `\c?` is delete.
+        match self.cur() {
+            c if '@' <= c && c <= '_' => Ok(char::from_u32(c as u32 - '@' as u32).unwrap()),
+            '?' => Ok('\u{7f}'),
+            c   => Err(ParseError::EscapeControlInvalid(c)),
+        }
     }
-    // Parse the `\w`, `\d`, ... types
+    // Parse the digits of `\x41`, `\x[263A]` or `\o[101]`. Assume the
+    // escape letter is the current char.
+    fn parse_escape_number(&mut self, radix: u32) -> Result<char> {
+        if self.peek('[') {
+            let letter = self.cur();
+            self.next(); // Advance to the `[`
+
+            return codepoint(&try!(self.parse_escape_brackets(letter)), radix)
+        }
+
+        let mut digits = String::new();
+        while self.chars.get(self.pos + 1).map_or(false, |c| c.is_digit(radix)) {
+            self.next();
+            digits.push(self.cur());
+        }
+
+        codepoint(&digits, radix)
+    }
+    // Parse the `\w`, `\d`, ... types or any other escape.
     fn parse_escape_set(&mut self) -> Result<Ast> {
-        self.parse_escape()
-            .map(|c| match c {
+        if !self.next() { return Err(ParseError::EscapeNotLast) }
+
+        Ok(match self.cur() {
             'd' => Ast::Set(PERLD.into(), Inclusive),
             'D' => Ast::Set(PERLD.into(), Exclusive),
-            'n' => Ast::Set('\n'.into(), Inclusive),
-            'N' => Ast::Set('\n'.into(), Exclusive),
-            't' => Ast::Set('\t'.into(), Inclusive),
+            'h' => Ast::Set(horizontal(), Inclusive),
+            'H' => Ast::Set(horizontal(), Exclusive),
+            // An ellipsis needs a char so `[\n .. \r]` starts from `\n` itself.
+            'n' if self.ellipsis_next() => Ast::Char('\n'),
+            'n' => Ast::Set(vertical(), Inclusive),
+            'N' => Ast::Set(vertical(), Exclusive),
+            't' => Ast::Char('\t'),
             'T' => Ast::Set('\t'.into(), Exclusive),
+            'v' => Ast::Set(vertical(), Inclusive),
+            'V' => Ast::Set(vertical(), Exclusive),
             's' => Ast::Set(PERLS.into(), Inclusive),
             'S' => Ast::Set(PERLS.into(), Exclusive),
             'w' => Ast::Set(PERLW.into(), Inclusive),
             'W' => Ast::Set(PERLW.into(), Exclusive),
-            c   => Ast::Char(c),
+            _   => Ast::Char(try!(self.parse_escaped_char())),
         })
     }
+    // Parse the `<?before foo>` or `<!after bar>`. Assume `<` is the current
+    // char and `?` or `!` is next.
+    //
+    // This crate reads these the other way around from Perl 6. `after` looks
+    // ahead for what comes after this spot and `before` looks behind for what
+    // came before it. See `perl_changes.md`.
+    fn parse_lookaround(&mut self) -> Result<Ast> {
+        self.next(); // Advance to the `?` or `!`
+        let polarity = if self.cur() == '?' { Positive } else { Negative };
+        let mut name = String::new();
+
+        loop {
+            if !self.next() { return Err(ParseError::ClassMustClose) }
+            if !self.cur().is_alphabetic() { break }
+
+            name.push(self.cur());
+        }
+
+        // Perl 6 looks ahead with `before` and behind with `after`.
+        let look = match (&*name, self.options.raku_lookaround) {
+            ("after", false) | ("before", true) => Ahead,
+            ("before", false) | ("after", true) => Behind,
+            _ => return Err(ParseError::LookaroundInvalid(name)),
+        };
+
+        if self.cur() == '>' { return Err(ParseError::LookaroundEmpty(name)) }
+
+        let options = self.options;
+        let vec = try!(self.parse_branches(Some('>')));
+        self.options = options;
+        if vec.iter().all(|ast| *ast == Ast::Empty) { return Err(ParseError::LookaroundEmpty(name)) }
+
+        Ok(Ast::Lookaround(vec, look, polarity))
+    }
+    // Parse a named class like `<alpha>` or an assertion like `<wb>`. A named
+    // class captures what it matches under its name unless it starts with a
+    // `.` like `<.alpha>`. Assume `<` is the current char.
+    fn parse_name(&mut self) -> Result<Ast> {
+        let capture = !self.peek('.');
+        if !capture { self.next(); } // Advance to the `.`
+        let mut name = String::new();
+
+        loop {
+            if !self.next() { return Err(ParseError::ClassMustClose) }
+            if self.cur() == '>' { break }
+
+            name.push(self.cur());
+        }
+
+        // Assertions are zero width so there's nothing to capture.
+        if name == "wb" { return Ok(Ast::Anchor(Anchor::WordBoundary)) }
+
+        match named_set(&name).or_else(|| named_rule(&name)) {
+            Some(ast) => if capture { Ok(Ast::NamedCapture(name, Box::new(ast))) } else { Ok(ast) },
+            None      => Err(ParseError::NameUnknown(name)),
+        }
+    }
+    // Parse the `< big small >` into `[ 'big' | 'small' ]`. Assume `<` is the
+    // current char.
+    fn parse_words(&mut self) -> Result<Ast> {
+        let mut branches = vec![];
+        let mut word = String::new();
+
+        loop {
+            if !self.next() { return Err(ParseError::ClassMustClose) }
+
+            let c = self.cur();
+            if (c == '>' || c.is_whitespace()) && !word.is_empty() {
+                branches.push(vec![Ast::Literal(mem::replace(&mut word, String::new()))]);
+            }
+
+            if c == '>' { break }
+            if !c.is_whitespace() { word.push(c) }
+        }
+
+        // Every word is a literal so the longest one which matches wins.
+        Ok(if branches.len() == 1 { branches.pop().unwrap().pop().unwrap() }
+           else { Ast::Alternation(branches, Longest) })
+    }
+    // Parse a Unicode property like `:Lu`, `:!Greek` or `:Script<Greek>`
+    // inside a `<>`. Assume `:` is the current char.
+    fn parse_property(&mut self) -> Result<Ast> {
+        let membership = if self.peek('!') {
+            self.next(); // Advance to the `!`
+            Exclusive
+        } else { Inclusive };
+        let mut name = String::new();
+
+        // The name ends at the `<` of a value or anything else which can't be
+        // part of a name such as the closing `>`.
+        let is_name = |c: char| c.is_alphanumeric() || c == '_' || c == '-';
+        while self.chars.get(self.pos + 1).map_or(false, |&c| is_name(c)) {
+            self.next();
+            name.push(self.cur());
+        }
+
+        let (name, kind) = if self.peek('<') {
+            self.next(); // Advance to the `<`
+            let kind = match &*loose(&name) {
+                "generalcategory" | "gc" => Property::GeneralCategory,
+                "script" | "sc"          => Property::Script,
+                _ => return Err(ParseError::PropertyUnknown(name)),
+            };
+            let mut value = String::new();
+
+            loop {
+                if !self.next() { return Err(ParseError::ClassMustClose) }
+                if self.cur() == '>' { break }
+
+                value.push(self.cur());
+            }
+
+            (value, kind)
+        } else { (name, Property::Any) };
+
+        match property(&name, kind) {
+            Some(table) => Ok(Ast::Set(table.into(), membership)),
+            None        => Err(ParseError::PropertyUnknown(name)),
+        }
+    }
+    // Parse the `$<year>=[\d**4]`. Assume `$` is the current char and `<`
+    // is next.
+    fn parse_named_capture(&mut self) -> Result<Ast> {
+        self.next(); // Advance to the `<`
+        let mut name = String::new();
+
+        while self.next() && self.cur() != '>' { name.push(self.cur()) }
+
+        let valid = self.cur() == '>' &&
+                    name.chars().next().map_or(false, |c| c.is_alphabetic() || c == '_') &&
+                    name.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-');
+        if !valid { return Err(ParseError::CaptureNameInvalid) }
+
+        if !(self.skip_whitespace() && self.cur() == '=' && self.skip_whitespace()) {
+            return Err(ParseError::CaptureMustBind(name))
+        }
+
+        match try!(self.parse_atom()) {
+            Ast::Empty => Err(ParseError::CaptureMustBind(name)),
+            ast => Ok(Ast::NamedCapture(name, Box::new(ast))),
+        }
+    }
+    // Parse a number such as the `12` in `** 12`. Assume the current char is
+    // the first digit.
+    fn parse_number(&mut self) -> Result<usize> {
+        let mut n = try!(self.cur().to_digit(10).ok_or(ParseError::RepeatCountInvalid)) as usize;
+
+        while self.next() {
+            match self.cur().to_digit(10) {
+                Some(d) => n = try!(n.checked_mul(10)
+                                     .and_then(|n| n.checked_add(d as usize))
+                                     .ok_or(ParseError::RepeatCountInvalid)),
+                None    => {
+                    self.prev();
+                    break
+                },
+            }
+        }
+
+        Ok(n)
+    }
+    // Parse the `*`, `+`, `?` or `** 2..5` which follows `atom`.
+    fn parse_quantifier(&mut self, atom: Option<Ast>) -> Result<Ast> {
+        let c = self.cur();
+        let atom = match atom {
+            // A comment or another quantifier can't be quantified.
+            None | Some(Ast::Empty) | Some(Ast::Repeat(..)) => return Err(ParseError::QuantifierNoAtom(c)),
+            // `$<num>=\d+` captures every repetition together.
+            Some(Ast::NamedCapture(name, atom)) => {
+                let repeat = try!(self.parse_quantifier(Some(*atom)));
+
+                return Ok(Ast::NamedCapture(name, Box::new(repeat)))
+            },
+            Some(atom) => Box::new(atom),
+        };
+
+        let (min, max) = match c {
+            '+' => (1, None),
+            '?' => (0, Some(1)),
+            '*' if self.peek('*') => {
+                self.next(); // Advance to the second `*`
+                try!(self.parse_repeat())
+            },
+            _   => (0, None),
+        };
+
+        Ok(Ast::Repeat(atom, min, max))
+    }
+    // The `**` has been parsed. Parse the count which follows it: `3`, `2..5`
+    // or `2..*`.
+    fn parse_repeat(&mut self) -> Result<(usize, Option<usize>)> {
+        if !self.skip_whitespace() { return Err(ParseError::RepeatCountInvalid) }
+
+        let min = try!(self.parse_number());
+        let pos = self.pos;
+
+        // Without a following `..` the count is exact.
+        if !(self.skip_whitespace() && self.cur() == '.' && self.peek('.')) {
+            self.pos = pos;
+
+            return Ok((min, Some(min)))
+        }
+
+        self.next(); // Advance to the second `.`
+        if !self.skip_whitespace() { return Err(ParseError::RepeatCountInvalid) }
+
+        if self.cur() == '*' { return Ok((min, None)) }
+
+        let max = try!(self.parse_number());
+        if min > max { return Err(ParseError::RepeatRangeBackwards(min, max)) }
+
+        Ok((min, Some(max)))
+    }
     // Parse the `'hello world'` and `"testing_this"`
     fn parse_literal(&mut self) -> Result<Ast> {
         let close = self.cur();
@@ -487,18 +1663,76 @@ impl Parser {
 
         Err(ParseError::LiteralMustClose(close))
     }
+    // Check if the next chars other than whitespace are `..`. Doesn't modify pos.
+    fn ellipsis_next(&self) -> bool {
+        let rest = &self.chars[self.pos + 1..];
+
+        trim_whitespace(rest).starts_with(&['.', '.'])
+    }
     // Check if next character matches `needle`. Doesn't modify pos.
     fn peek(&mut self, needle: char) -> bool {
-        let mut ret = false;
+        if !self.next() { return false }
 
-        if self.next() &&
-           self.cur() == needle { ret = true; }
-
-        // `self.next()` always increments pos even if it fails to find an element. So decrement.
+        let ret = self.cur() == needle;
+        // `self.next()` advanced pos so step back.
         self.prev();
 
         ret
     }
+    // True if the `<` at the current char starts a `< big small >` word list.
+    // Whitespace must follow the `<` and the first word can't start like the
+    // operands and ops of a class such as `< [a] + [b] >` do.
+    fn is_word_list(&self) -> bool {
+        let rest = &self.chars[self.pos + 1..];
+
+        if !rest.first().map_or(false, |c| c.is_whitespace()) { return false }
+
+        let start = match rest.iter().position(|c| !c.is_whitespace()) {
+            Some(start) => start,
+            None => return false,
+        };
+
+        match rest[start] {
+            '[' | '(' | ':' | '>' | '-' | '+' | '|' | '&' | '^' => return false,
+            _ => {},
+        }
+
+        // A name then a set operator and another operand like `< alpha + [_] >`
+        // is a class too. The operator must be spaced out from the name since
+        // `< upper-deck >` is a word with a `-` in it.
+        let name = leading_name(&rest[start..]);
+        let after = &rest[start + name.chars().count()..];
+
+        if !is_name(&name) || !after.first().map_or(false, |c| c.is_whitespace()) { return true }
+
+        let after = trim_whitespace(after);
+        if !after.first().map_or(false, |&c| "-+|&^".contains(c)) { return true }
+
+        let operand = trim_whitespace(&after[1..]);
+        !match operand.first() {
+            Some(&'[') | Some(&'(') | Some(&':') => true,
+            Some(_) => is_name(&leading_name(operand)),
+            None => false,
+        }
+    }
+    // True if the `<` at the current char holds only a name like `<alpha>` or
+    // `<.ws>`. Anything more such as `<alpha + [_]>` is a class.
+    fn is_lone_name(&self) -> bool {
+        let mut rest = self.chars[self.pos + 1..].iter().cloned().peekable();
+
+        if rest.peek() == Some(&'.') { rest.next(); }
+        if !rest.peek().map_or(false, |c| c.is_alphabetic()) { return false }
+
+        rest.find(|&c| !(c.is_alphanumeric() || c == '_')) == Some('>')
+    }
+    // Advance to the next char which isn't whitespace. True if one is found.
+    fn skip_whitespace(&mut self) -> bool {
+        while self.next() {
+            if !self.cur().is_whitespace() { return true }
+        }
+
+        false
+    }
     // True if prev finds another char.
     fn prev(&mut self) -> bool {
         if self.pos == 0 { false }
@@ -58,6 +58,14 @@ impl Set {
     }
     pub fn new() -> Self { Set(BTreeSet::new()) }
     pub fn insert(&mut self, value: Range) {
+        // Tables are sorted so building a set from one only ever adds past the
+        // end. Nothing can overlap then so skip rebuilding the whole set.
+        let past_end = self.0.iter().next_back().map_or(false, |&Range(_, max)| value.0 > max.next());
+        if past_end && value.0 <= value.1 {
+            self.0.insert(value);
+            return
+        }
+
         let mut ret = BTreeSet::new();
         // value is a complete subset of one of the other ranges.
         let mut subset = false;
@@ -121,9 +129,6 @@ impl Set {
                 else if min_val > min && max_val < max {
                     ret.insert(Range(min, min_val.prev()));
                     ret.insert(Range(max_val.next(), max));
-
-                    // Current piece was a superset so value cannot be anywhere else.
-                    break;
                 // value is a superset to the current so don't add current.
                 } else if min_val <= min && max_val >= max {}
                 // value is disjoint with current so add current.
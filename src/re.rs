@@ -1,15 +1,17 @@
-use parse::{self, Ast};
+use compile::{self, Program};
+use parse;
 use collapse;
+use std::ops::Index;
+use vm::{self, Vm};
 
 #[derive(Debug)]
-pub struct Regex(Vec<Ast>);
+pub struct Regex {
+    prog: Program,
+}
 
 impl Regex {
     pub fn new(s: &str) -> Regex {
-        let vec = parse::parse(s).unwrap();
-        let vec = collapse::collapse(vec);
-
-        Regex(vec)
+        RegexBuilder::new(s).build()
     }
     /// Returns true if and only if the regex matches the string given.
     ///
@@ -31,50 +33,259 @@ impl Regex {
     /// # }
     /// ```
     pub fn is_match(&self, s: &str) -> bool {
-        let match_start = MatchStart { ast: &self.0[0], chars: s, cur: 0 };
-        let mut matches = false;
-
-        // Iterates over the substrings which could *possibly* match based
-        // only on the first Ast.
-        for substr in match_start {
-            let mut substr = substr;
-
-            for ast in &self.0 {
-                // No way to return a result based on if the loop doesn't break. This
-                // is the workaround.
-                matches = true;
-
-                if let Some(trimmed) = ast.trim_left_match(substr) {
-                    substr = trimmed;
-                } else {
-                    matches = false;
-                    break
-                }
-            }
-
-            if matches { return true }
+        vm::search(&self.prog, s).is_some()
+    }
+    /// Returns the leftmost match in the string given if there is one.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # extern crate rules; use rules::re::Regex;
+    /// # fn main() {
+    /// let number = Regex::new(r"\d+");
+    /// let found = number.find("It took 42 tries.").unwrap();
+    ///
+    /// assert_eq!(found.as_str(), "42");
+    /// assert_eq!((found.start(), found.end()), (8, 10));
+    /// # }
+    /// ```
+    pub fn find<'t>(&self, s: &'t str) -> Option<Match<'t>> {
+        vm::search(&self.prog, s).map(|(start, end)| Match::new(s, start, end))
+    }
+    /// Returns the leftmost match along with what each `( )` captured.
+    /// Captures are numbered from `$0` in the order their `(` appears. Each
+    /// branch of an alternation numbers its captures from the same place and a
+    /// capture nested inside another belongs to the outer one so it isn't
+    /// numbered. A quantified capture keeps only its last repetition.
+    ///
+    /// `$<name>=` binds a name to the atom which follows it. A named `( )`
+    /// doesn't get a number.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # extern crate rules; use rules::re::Regex;
+    /// # fn main() {
+    /// let date = Regex::new(r"(\d+) '-' (\d+) [ '-' (\d+) ]?");
+    /// let caps = date.captures("Due 2015-06").unwrap();
+    ///
+    /// assert_eq!(&caps[0], "2015");
+    /// assert_eq!(&caps[1], "06");
+    /// // `$2` didn't take part in the match.
+    /// assert!(caps.get(2).is_none());
+    ///
+    /// let date = Regex::new(r"$<year>=[\d**4] '-' $<month>=(\d**2)");
+    /// let caps = date.captures("Due 2015-06").unwrap();
+    ///
+    /// assert_eq!(caps.name("year").unwrap().as_str(), "2015");
+    /// assert_eq!(caps.name("month").unwrap().as_str(), "06");
+    /// # }
+    /// ```
+    pub fn captures<'t>(&self, s: &'t str) -> Option<Captures<'t>> {
+        let mut vm = Vm::new(&self.prog, s);
+
+        vm.search(0).map(|(start, end)| Captures {
+            txt: s,
+            whole: Match::new(s, start, end),
+            slots: vm.slots().to_vec(),
+            names: self.prog.names.clone(),
+        })
+    }
+    /// Returns the names of every named capture in the order they first
+    /// appear.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # extern crate rules; use rules::re::Regex;
+    /// # fn main() {
+    /// let date = Regex::new(r"$<year>=[\d**4] '-' $<month>=(\d**2)");
+    /// assert_eq!(date.capture_names(), ["year", "month"]);
+    /// # }
+    /// ```
+    pub fn capture_names(&self) -> &[String] {
+        &self.prog.names
+    }
+    /// Returns an iterator over every non-overlapping match in the string
+    /// given from left to right.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # extern crate rules; use rules::re::Regex;
+    /// # fn main() {
+    /// let number = Regex::new(r"\d+");
+    /// let found: Vec<&str> = number.find_iter("1 22 333").map(|m| m.as_str()).collect();
+    ///
+    /// assert_eq!(found, vec!["1", "22", "333"]);
+    /// # }
+    /// ```
+    pub fn find_iter<'r, 't>(&'r self, s: &'t str) -> Matches<'r, 't> {
+        Matches { vm: Vm::new(&self.prog, s),
+                  txt: s,
+                  cur: 0,
+                  last_end: None,
+        }
+    }
+}
+
+/// Builds a `Regex` with settings other than the defaults.
+///
+/// # Example
+///
+/// ```rust
+/// # extern crate rules; use rules::re::RegexBuilder;
+/// # fn main() {
+/// // Perl 6 looks ahead with `before`.
+/// let re = RegexBuilder::new(r"foo <?before bar>").raku_lookaround(true).build();
+/// assert_eq!(re.find("foobar").unwrap().as_str(), "foo");
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct RegexBuilder {
+    pattern: String,
+    options: parse::Options,
+}
+
+impl RegexBuilder {
+    pub fn new(s: &str) -> RegexBuilder {
+        RegexBuilder { pattern: s.to_owned(),
+                       options: parse::Options::default(),
         }
+    }
+    /// Make `before` and `after` in lookarounds mean what they do in Perl 6
+    /// so `<?before bar>` looks ahead and `<?after foo>` looks behind. Off by
+    /// default.
+    pub fn raku_lookaround(&mut self, yes: bool) -> &mut RegexBuilder {
+        self.options.raku_lookaround = yes;
+        self
+    }
+    /// Match as if the pattern started with `:i` so case is ignored
+    /// everywhere. A `:!i` inside the pattern still turns it back off. Off by
+    /// default.
+    pub fn ignorecase(&mut self, yes: bool) -> &mut RegexBuilder {
+        self.options.ignorecase = yes;
+        self
+    }
+    /// Match as if the pattern started with `:m` so combining marks are
+    /// ignored everywhere. Off by default.
+    pub fn ignoremark(&mut self, yes: bool) -> &mut RegexBuilder {
+        self.options.ignoremark = yes;
+        self
+    }
+    /// Match as if the pattern started with `:s` so whitespace after an atom
+    /// matches `<.ws>`. Off by default.
+    pub fn sigspace(&mut self, yes: bool) -> &mut RegexBuilder {
+        self.options.sigspace = yes;
+        self
+    }
+    pub fn build(&self) -> Regex {
+        let vec = parse::parse_with(&self.pattern, self.options).unwrap();
+        let vec = collapse::collapse(vec);
 
-        false
+        Regex { prog: compile::compile(&vec) }
     }
 }
 
-// A struct to allow an Iterator to be created which will return substrings
-// which start where a single Ast matches.
-struct MatchStart<'a> {
-    ast: &'a Ast,
-    chars: &'a str,
-    cur:  usize,
+/// A single match of a regex in a string. The offsets are in bytes.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Match<'t> {
+    txt: &'t str,
+    start: usize,
+    end: usize,
 }
 
-impl<'a> Iterator for MatchStart<'a> {
-    type Item = &'a str;
+impl<'t> Match<'t> {
+    fn new(txt: &'t str, start: usize, end: usize) -> Self {
+        Match { txt, start, end }
+    }
+    /// The byte offset where the match starts.
+    pub fn start(&self) -> usize { self.start }
+    /// The byte offset just past where the match ends.
+    pub fn end(&self) -> usize { self.end }
+    /// The text which was matched.
+    pub fn as_str(&self) -> &'t str { &self.txt[self.start..self.end] }
+}
+
+/// What a single match and each of its captures matched. Created by
+/// [`captures()`](struct.Regex.html#method.captures).
+#[derive(Clone, Debug)]
+pub struct Captures<'t> {
+    txt: &'t str,
+    whole: Match<'t>,
+    slots: Vec<Option<usize>>,
+    names: Vec<String>,
+}
+
+impl<'t> Captures<'t> {
+    /// Returns what capture `$i` matched. `None` if it didn't take part in
+    /// the match or doesn't exist.
+    pub fn get(&self, i: usize) -> Option<Match<'t>> {
+        match (self.slots.get(2 * i), self.slots.get(2 * i + 1)) {
+            (Some(&Some(start)), Some(&Some(end))) => Some(Match::new(self.txt, start, end)),
+            _ => None,
+        }
+    }
+    /// Returns what the capture called `name` matched. `None` if it didn't
+    /// take part in the match or doesn't exist.
+    pub fn name(&self, name: &str) -> Option<Match<'t>> {
+        self.names
+            .iter()
+            .position(|n| n == name)
+            .and_then(|index| self.get(self.len() + index))
+    }
+    /// The number of positional captures in the regex.
+    pub fn len(&self) -> usize { self.slots.len() / 2 - self.names.len() }
+    /// The whole match.
+    pub fn whole(&self) -> Match<'t> { self.whole }
+}
 
-    fn next(&mut self) -> Option<&'a str> {
-        if let Some(pos) = self.ast.find(&self.chars[self.cur..]) {
-            self.cur += pos + 1;
+/// `caps[i]` is the text capture `$i` matched. Panics if it didn't take
+/// part in the match.
+impl<'t> Index<usize> for Captures<'t> {
+    type Output = str;
 
-            Some(&self.chars[self.cur-1..])
-        } else { None }
+    fn index(&self, i: usize) -> &str {
+        self.get(i)
+            .map(|m| m.as_str())
+            .unwrap_or_else(|| panic!("Capture `${}` didn't match.", i))
+    }
+}
+
+/// An iterator over every non-overlapping match in a string. Created by
+/// [`find_iter()`](struct.Regex.html#method.find_iter).
+pub struct Matches<'r, 't> {
+    vm: Vm<'r, 't>,
+    txt: &'t str,
+    // Where to start searching for the next match.
+    cur: usize,
+    last_end: Option<usize>,
+}
+
+impl<'r, 't> Iterator for Matches<'r, 't> {
+    type Item = Match<'t>;
+
+    fn next(&mut self) -> Option<Match<'t>> {
+        loop {
+            if self.cur > self.txt.len() { return None }
+
+            let (start, end) = match self.vm.search(self.cur) {
+                Some(found) => found,
+                None        => return None,
+            };
+
+            // An empty match must still make progress so step past the next char.
+            self.cur = if start == end {
+                end + self.txt[end..].chars()
+                                     .next()
+                                     .map_or(1, |c| c.len_utf8())
+            } else { end };
+
+            // An empty match right where the last one ended would overlap it.
+            if start == end && Some(end) == self.last_end { continue }
+            self.last_end = Some(end);
+
+            return Some(Match::new(self.txt, start, end))
+        }
     }
 }
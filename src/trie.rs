@@ -0,0 +1,86 @@
+//! A prefix tree of words.
+//!
+//! A `< big small >` word list may hold thousands of words. Trying each of
+//! them in turn at every position is far too slow so they're stored in a
+//! `Trie` instead. A single walk along the text then finds every word which
+//! starts there no matter how many words there are.
+
+use range_set::{Range, Set};
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct Node {
+    // Sorted by char so the next step can be binary searched.
+    children: Vec<(char, usize)>,
+    // A word ends here.
+    word: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Trie {
+    // The root is always the first node.
+    nodes: Vec<Node>,
+}
+
+impl Trie {
+    pub fn new() -> Self { Trie { nodes: vec![Node::default()] } }
+    // Add a word. Adding a word which is already there changes nothing.
+    pub fn insert(&mut self, word: &str) {
+        let mut node = 0;
+
+        for c in word.chars() {
+            node = match self.nodes[node].children.binary_search_by_key(&c, |&(c, _)| c) {
+                Ok(i) => self.nodes[node].children[i].1,
+                Err(i) => {
+                    let next = self.nodes.len();
+                    self.nodes.push(Node::default());
+                    self.nodes[node].children.insert(i, (c, next));
+
+                    next
+                },
+            };
+        }
+
+        self.nodes[node].word = true;
+    }
+    // The byte length of every word which the text starts with, shortest
+    // first. The chars may have been folded so they don't come straight from
+    // the text. Each comes with the byte offset in the text just past it and a
+    // word can only end where there is an offset.
+    pub fn walk<I>(&self, chars: I) -> Vec<usize>
+        where I: Iterator<Item = (char, Option<usize>)>
+    {
+        let mut lens = vec![];
+        let mut node = 0;
+
+        if self.nodes[node].word { lens.push(0) }
+
+        for (c, end) in chars {
+            match self.step(node, c) {
+                Some(next) => node = next,
+                None => break,
+            }
+
+            match end {
+                Some(end) if self.nodes[node].word => lens.push(end),
+                _ => {},
+            }
+        }
+
+        lens
+    }
+    // Every char a word can start with.
+    pub fn first_chars(&self) -> Set {
+        let mut set = Set::new();
+
+        for &(c, _) in &self.nodes[0].children { set.insert(Range(c, c)) }
+
+        set
+    }
+    fn step(&self, node: usize, c: char) -> Option<usize> {
+        let children = &self.nodes[node].children;
+
+        children.binary_search_by_key(&c, |&(c, _)| c)
+                .ok()
+                .map(|i| children[i].1)
+    }
+}
@@ -0,0 +1,381 @@
+//! A backtracking virtual machine which runs a compiled `Program`.
+//!
+//! Every `(instruction, position)` pair is only ever explored once. Whether a
+//! pair leads to a match doesn't depend on how it was reached so a pair which
+//! failed before will fail again. This keeps the search from going
+//! exponential and stops a loop whose body matches nothing from spinning forever.
+
+use compile::{Inst, Program};
+use parse::Ast;
+use parse::Polarity::Positive;
+use std::collections::{HashMap, HashSet};
+
+// Return the `(start, end)` byte offsets of the leftmost match in `txt`.
+pub fn search(prog: &Program, txt: &str) -> Option<(usize, usize)> {
+    Vm::new(prog, txt).search(0)
+}
+
+// Remembers which `(instruction, position)` pairs have been tried. A bit for
+// each is cheapest but a bit for every pair in a long text adds up, so bits
+// are only kept for the blocks of positions a run has reached. A search
+// never goes back before where it started so the blocks behind it are
+// dropped as it moves along. Once the bits would go over `max_bits` the rest
+// of the pairs go in a `HashSet` instead. Measuring a prefix only looks at a
+// few positions so those use the `HashSet` from the start.
+//
+// A run which matched may have passed through pairs a later search needs so
+// every position it reached is forgotten again afterwards.
+struct Visited {
+    // The number of instructions in the program.
+    len: usize,
+    max_bits: usize,
+    // Bits for each block of `BLOCK` positions. Empty until it's reached.
+    blocks: Vec<Vec<u64>>,
+    // How many blocks have bits and the first one which may.
+    used: usize,
+    first: usize,
+    spilled: HashSet<(usize, usize)>,
+    // The lowest and highest positions marked during the current run.
+    reached: Option<(usize, usize)>,
+}
+
+// Positions per block of bits.
+const BLOCK: usize = 256;
+// The most bits a search keeps at once. 4 MiB.
+const MAX_BITS: usize = 1 << 25;
+
+impl Visited {
+    fn new(prog: &Program, max_bits: usize) -> Self {
+        Visited { len: prog.insts.len(),
+                  max_bits,
+                  blocks: vec![],
+                  used: 0,
+                  first: 0,
+                  spilled: HashSet::new(),
+                  reached: None,
+        }
+    }
+    fn start_run(&mut self) { self.reached = None }
+    // Unmark every pair at a position the current run reached.
+    fn forget_run(&mut self) {
+        let (low, high) = match self.reached.take() {
+            Some(reached) => reached,
+            None => return,
+        };
+
+        for block in low / BLOCK..(high / BLOCK + 1).min(self.blocks.len()) {
+            let from = low.max(block * BLOCK) - block * BLOCK;
+            let to = high.min(block * BLOCK + BLOCK - 1) - block * BLOCK + 1;
+
+            clear_bits(&mut self.blocks[block], from * self.len, to * self.len);
+        }
+
+        if !self.spilled.is_empty() { self.spilled.retain(|&(_, at)| at < low || at > high) }
+    }
+    // Forget every pair before `pos`. Nothing before it will be tried again.
+    fn forget_before(&mut self, pos: usize) {
+        let first = pos / BLOCK;
+        if first <= self.first { return }
+
+        for block in self.first..first.min(self.blocks.len()) {
+            if !self.blocks[block].is_empty() { self.used -= 1 }
+            self.blocks[block] = vec![];
+        }
+
+        self.first = first;
+        if !self.spilled.is_empty() { self.spilled.retain(|&(_, at)| at >= first * BLOCK) }
+    }
+    // Mark a pair as visited. False if it already was.
+    fn insert(&mut self, pc: usize, pos: usize) -> bool {
+        let (block, bit) = (pos / BLOCK, (pos % BLOCK) * self.len + pc);
+        let room = (self.used + 1) * BLOCK * self.len <= self.max_bits;
+
+        if block >= self.blocks.len() && room { self.blocks.resize(block + 1, vec![]) }
+
+        let marked = match self.blocks.get_mut(block) {
+            Some(ref mut bits) if !bits.is_empty() || (block >= self.first && room) => {
+                if bits.is_empty() {
+                    **bits = vec![0; (BLOCK * self.len + 63) / 64];
+                    self.used += 1;
+                }
+
+                let (index, mask) = (bit / 64, 1 << (bit % 64));
+                let marked = bits[index] & mask == 0;
+                bits[index] |= mask;
+
+                marked
+            },
+            _ => self.spilled.insert((pc, pos)),
+        };
+
+        if marked {
+            self.reached = Some(self.reached.map_or((pos, pos), |(low, high)| {
+                (low.min(pos), high.max(pos))
+            }));
+        }
+
+        marked
+    }
+}
+
+// Clear the bits in `from..to`.
+fn clear_bits(bits: &mut [u64], mut from: usize, to: usize) {
+    if bits.is_empty() { return }
+
+    while from < to {
+        let (index, offset) = (from / 64, from % 64);
+        let n = (64 - offset).min(to - from);
+        let mask = if n == 64 { !0 } else { ((1 << n) - 1) << offset };
+
+        bits[index] &= !mask;
+        from += n;
+    }
+}
+
+// When a run is finished.
+#[derive(Clone, Copy, PartialEq)]
+enum Mode {
+    // The first match found wins.
+    First,
+    // Every path is explored and the furthest end wins.
+    Longest,
+}
+
+// Something left to do when backtracking.
+enum Job {
+    // Try again from this `(instruction, position)`.
+    Inst(usize, usize),
+    // Put a capture slot back the way it was.
+    Restore(usize, Option<usize>),
+}
+
+// Runs a `Program` against a single `txt`. The same `Vm` can search `txt`
+// many times without redoing work which already failed.
+pub struct Vm<'p, 't> {
+    prog: &'p Program,
+    txt: &'t str,
+    stack: Vec<Job>,
+    visited: Visited,
+    // Positions recorded by `Save` for the current run.
+    slots: Vec<Option<usize>>,
+    // A `Vm` for each lookaround by the index of its instruction. Whether a
+    // pair leads to a match doesn't depend on where the lookaround was
+    // checked from either so what failed once is never tried again.
+    looks: HashMap<usize, Vm<'p, 't>>,
+}
+
+impl<'p, 't> Vm<'p, 't> {
+    pub fn new(prog: &'p Program, txt: &'t str) -> Self {
+        Vm::with_visited(prog, txt, Visited::new(prog, MAX_BITS))
+    }
+    // For programs which only run at a few positions.
+    fn sparse(prog: &'p Program, txt: &'t str) -> Self {
+        Vm::with_visited(prog, txt, Visited::new(prog, 0))
+    }
+    fn with_visited(prog: &'p Program, txt: &'t str, visited: Visited) -> Self {
+        Vm { prog,
+             txt,
+             stack: vec![],
+             visited,
+             slots: vec![None; 2 * (prog.captures + prog.names.len())],
+             looks: HashMap::new(),
+        }
+    }
+    // The capture slots of the last successful search.
+    pub fn slots(&self) -> &[Option<usize>] { &self.slots }
+    // Return the `(start, end)` byte offsets of the leftmost match which
+    // starts at or after `from`.
+    pub fn search(&mut self, from: usize) -> Option<(usize, usize)> {
+        let match_start = MatchStart { ast: self.prog.start.as_ref(), chars: self.txt, cur: from };
+
+        // Only positions where the first `Ast` matches could start a match.
+        for start in match_start {
+            self.forget_before(start);
+            self.visited.start_run();
+
+            if let Some(end) = self.run(start, Mode::First) {
+                self.visited.forget_run();
+
+                return Some((start, end))
+            }
+        }
+
+        None
+    }
+    // Nothing before `pos` is tried again by this search. A lookbehind still
+    // looks back there though so it keeps everything.
+    fn forget_before(&mut self, pos: usize) {
+        let prog = self.prog;
+        self.visited.forget_before(pos);
+
+        for (&pc, vm) in &mut self.looks {
+            if let Inst::Ahead(..) = prog.insts[pc] { vm.forget_before(pos) }
+        }
+    }
+    // Check if the lookaround at `pc` matches from `pos`.
+    fn look(&mut self, pc: usize, prog: &'p Program, pos: usize) -> bool {
+        let txt = self.txt;
+        let vm = self.looks.entry(pc).or_insert_with(|| Vm::new(prog, txt));
+
+        vm.visited.start_run();
+        let found = vm.run(pos, Mode::First).is_some();
+        if found { vm.visited.forget_run() }
+
+        found
+    }
+    // Try to match starting at `start`. Returns where the match ended.
+    // Backwards it's where the match started.
+    fn run(&mut self, start: usize, mode: Mode) -> Option<usize> {
+        let (prog, txt) = (self.prog, self.txt);
+        let mut end = None;
+        for slot in &mut self.slots { *slot = None }
+        self.stack.push(Job::Inst(0, start));
+
+        while let Some(job) = self.stack.pop() {
+            let (mut pc, mut pos) = match job {
+                Job::Inst(pc, pos) => (pc, pos),
+                Job::Restore(slot, old) => {
+                    self.slots[slot] = old;
+                    continue
+                },
+            };
+
+            while self.visited.insert(pc, pos) {
+                match prog.insts[pc] {
+                    Inst::Ahead(ref sub, polarity) |
+                    Inst::Behind(ref sub, polarity) => {
+                        if self.look(pc, sub, pos) != (polarity == Positive) { break }
+                        pc += 1;
+                    },
+                    Inst::Assert(ref anchor) => {
+                        if !anchor.is_match(txt, pos) { break }
+                        pc += 1;
+                    },
+                    Inst::Atom(ref ast) => match ast.trim_left_match(&txt[pos..]) {
+                        Some(rest) => {
+                            pos = txt.len() - rest.len();
+                            pc += 1;
+                        },
+                        None => break,
+                    },
+                    Inst::Back(ref ast) => {
+                        // It has to end here going forwards too. That rules out
+                        // ending between a `\r\n` or before the marks of a char.
+                        let ends_here = |start: usize| {
+                            ast.trim_left_match(&txt[start..]).map(str::len) == Some(txt.len() - pos)
+                        };
+                        let starts: Vec<_> = ast.trim_right_matches(&txt[..pos])
+                            .into_iter()
+                            .map(|before| before.len())
+                            .filter(|&start| ends_here(start))
+                            .collect();
+
+                        match starts.split_first() {
+                            Some((&start, rest)) => {
+                                for &other in rest.iter().rev() { self.stack.push(Job::Inst(pc + 1, other)) }
+                                pos = start;
+                                pc += 1;
+                            },
+                            None => break,
+                        }
+                    },
+                    Inst::Jump(to) => pc = to,
+                    Inst::Longest(ref branches) => {
+                        let mut ends: Vec<_> = branches.iter()
+                            .filter_map(|branch| {
+                                Vm::sparse(&branch.prefix, txt)
+                                    .run(pos, Mode::Longest)
+                                    .map(|end| (end, branch.goto))
+                            })
+                            .collect();
+
+                        // Longest first. The sort is stable so ties keep the order
+                        // they were written in.
+                        ends.sort_by(|a, b| b.0.cmp(&a.0));
+
+                        // Branches whose prefix can't match are never tried.
+                        match ends.split_first() {
+                            Some((&(_, goto), rest)) => {
+                                for &(_, other) in rest.iter().rev() { self.stack.push(Job::Inst(other, pos)) }
+                                pc = goto;
+                            },
+                            None => break,
+                        }
+                    },
+                    Inst::Match => match mode {
+                        Mode::First => {
+                            self.stack.clear();
+
+                            return Some(pos)
+                        },
+                        Mode::Longest => {
+                            if end.map_or(true, |end| pos > end) { end = Some(pos) }
+                            break
+                        },
+                    },
+                    Inst::Save(slot) => {
+                        self.stack.push(Job::Restore(slot, self.slots[slot]));
+                        self.slots[slot] = Some(pos);
+                        pc += 1;
+                    },
+                    Inst::Split(first, second) => {
+                        self.stack.push(Job::Inst(second, pos));
+                        pc = first;
+                    },
+                    Inst::Words(ref trie, fold) => {
+                        let lens = trie.walk(fold.chars(&txt[pos..]));
+
+                        // The longest word goes first. The rest are pushed so
+                        // the next longest is popped first when backtracking.
+                        match lens.split_last() {
+                            Some((&longest, rest)) => {
+                                for &len in rest { self.stack.push(Job::Inst(pc + 1, pos + len)) }
+                                pos += longest;
+                                pc += 1;
+                            },
+                            None => break,
+                        }
+                    },
+                }
+            }
+        }
+
+        end
+    }
+}
+
+// An Iterator which returns the byte offsets where a match could possibly
+// start based only on the first `Ast`. Without an `Ast` every position is
+// returned.
+struct MatchStart<'a> {
+    ast: Option<&'a Ast>,
+    chars: &'a str,
+    cur:  usize,
+}
+
+impl<'a> Iterator for MatchStart<'a> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.cur > self.chars.len() { return None }
+
+        let found = match self.ast {
+            // An anchored regex only needs to try where the anchor holds.
+            Some(&Ast::Anchor(ref anchor)) => {
+                anchor.find(self.chars, self.cur).map(|pos| pos - self.cur)
+            },
+            Some(ast) => ast.find(&self.chars[self.cur..]),
+            None      => Some(0),
+        };
+
+        found.map(|pos| {
+            let pos = self.cur + pos;
+            // Step over the whole char. Past the end, step off the end.
+            self.cur = pos + self.chars[pos..].chars()
+                                              .next()
+                                              .map_or(1, |c| c.len_utf8());
+
+            pos
+        })
+    }
+}
@@ -12,3 +12,9 @@ fn char_class() {
     assert_eq!(vec![Set(set.clone(), Exclusive)], simplify(r"< - [ a ] >"));
     assert_eq!(vec![Set(set, Inclusive)], simplify(r"< [ abc ] - [ b ] - [ cde ] - >"));
 }
+#[test]
+fn negated() {
+    let set: range_set::Set = vec![Char('1')].into();
+    assert_eq!(vec![Set(set, Inclusive)], simplify(r"< [ a1 ] - [ \D ] >"));
+    assert_eq!(simplify(r"< -[ \d ] - [ a ] >"), simplify(r"< [ \D ] - [ a ] >"));
+}
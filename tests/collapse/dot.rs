@@ -0,0 +1,9 @@
+use rules::parse::Ast::*;
+
+use super::simplify;
+
+#[test]
+fn dot() {
+    assert_eq!(vec![Char('a'), Dot, Char('b')], simplify(r"a . b"));
+    assert_eq!(vec![Repeat(Box::new(Dot), 0, None)], simplify(r".*"));
+}
@@ -0,0 +1,15 @@
+use rules::parse::Ast::*;
+use rules::parse::Faction::*;
+use rules::parse::Membership::*;
+
+use super::simplify;
+
+#[test]
+fn contents_collapse() {
+    // Classes and comments inside of groups are collapsed too.
+    let set = Set(vec![Char('a'), Char('b')].into(), Inclusive);
+    assert_eq!(vec![Group(vec![set.clone()], Capture)], simplify(r"( <[a] + [b]> )"));
+    assert_eq!(vec![Group(vec![Char('a'), set], NonCapture)],
+               simplify(r"[ a # Comment
+                            <[a] + [b]> ]"));
+}
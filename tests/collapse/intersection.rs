@@ -17,3 +17,8 @@ fn char_class_w_empty() {
     // empty class is not allowed.
     simplify(r"< & [ abc ]>");
 }
+#[test]
+fn negated() {
+    let set = vec![Char('a'), Char('b')].into();
+    assert_eq!(vec![Set(set, Inclusive)], simplify(r"< [ \D ] & [ a1b2 ] >"));
+}
@@ -5,6 +5,9 @@ fn simplify(s: &str) -> Vec<Ast> {
     collapse::collapse(parse::parse(s).unwrap())
 }
 
+mod dot;
+mod groups;
+mod precedence;
 mod union;
 mod intersection;
 mod difference;
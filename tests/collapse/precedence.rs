@@ -0,0 +1,32 @@
+use rules::parse::Ast::*;
+use rules::parse::Membership::*;
+
+use super::simplify;
+
+fn set(s: &str) -> Vec<rules::parse::Ast> {
+    vec![Set(s.chars().map(Char).collect::<Vec<_>>().into(), Inclusive)]
+}
+
+#[test]
+fn left_to_right() {
+    assert_eq!(set("bcde"), simplify(r"< [ a..e ] - [ a ] + [ a ] - [ a ] >"));
+    assert_eq!(set("ae"), simplify(r"< [ abc ] ^ [ bc ] + [ e ] >"));
+}
+#[test]
+fn intersection_first() {
+    // `[ b ] & [ bc ]` is applied before either `-` or `+`.
+    assert_eq!(set("ac"), simplify(r"< [ abc ] - [ b ] & [ bc ] >"));
+    assert_eq!(set("ab"), simplify(r"< [ a ] + [ b ] & [ bc ] >"));
+    assert_eq!(set("b"), simplify(r"< [ a ] & [ b ] + [ b ] >"));
+}
+#[test]
+fn parens() {
+    assert_eq!(set("bcdf"), simplify(r"< [ a..f ] - ( [ aeiou ] + [ y ] ) >"));
+    assert_eq!(set("bcdfghj"), simplify(r"< [ a..j ] - ( [ aeiou ] & [ a..f ] ) - [ i ] >"));
+    assert_eq!(set("ab"), simplify(r"< ( [ a ] + [ b ] & [ c ] ) + ( [ b ] ) >"));
+    assert_eq!(set("c"), simplify(r"< [ abc ] - ( [ a ] + ( [ bc ] - [ c ] ) ) >"));
+}
+#[test]
+fn parens_negate() {
+    assert_eq!(simplify(r"< -[ ab ] >"), simplify(r"< -( [ a ] + [ b ] ) >"));
+}
@@ -11,3 +11,8 @@ fn char_class() {
     assert_eq!(vec![set.clone()], simplify(r"< ^ [ a ] ^ >"));
     assert_eq!(vec![set], simplify(r"<[ \d abc ] ^ [ \d bcde ] ^ [ de ]>"));
 }
+#[test]
+fn negated() {
+    // `a` is already in `[\D]` so it drops out while `1` joins.
+    assert_eq!(simplify(r"< [ \D ] - [ a ] + [ 1 ] >"), simplify(r"< [ \D ] ^ [ a1 ] >"));
+}
@@ -25,3 +25,9 @@ fn char_class() {
     assert_eq!(vec![empty.clone()], simplify(r"< [ abc \d \D ] >"));
     assert_eq!(vec![empty], simplify(r"< [ abc ] + [ \d ] + [ \D ] >"));
 }
+#[test]
+fn negated() {
+    // Only chars which are both digits and whitespace are left out.
+    assert_eq!(vec![Set(vec![].into(), Exclusive)], simplify(r"< [ \D ] + [ \S ] >"));
+    assert_eq!(simplify(r"< [ \D ] >"), simplify(r"< [ \D ] + [ ab ] >"));
+}
@@ -3,4 +3,7 @@ extern crate rules;
 mod collapse;
 mod parse;
 mod range_set;
+mod trie;
 mod re;
+mod unicode;
+mod vm;
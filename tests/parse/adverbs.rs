@@ -0,0 +1,54 @@
+use rules::parse::Ast::*;
+use rules::parse::Choice::*;
+use rules::parse::Faction::*;
+use rules::parse::Fold;
+
+use super::parse;
+
+fn folded(s: &str) -> rules::parse::Ast {
+    Folded(s.to_owned(), Fold { case: true, ..Fold::default() })
+}
+
+#[test]
+fn ignorecase() {
+    assert_eq!(vec![Empty, folded("a"), folded("bc")], parse(r":i A 'bC'"));
+    assert_eq!(parse(r":i A 'bC'"), parse(r":ignorecase a 'bc'"));
+}
+#[test]
+fn ignoremark() {
+    let fold = Fold { marks: true, ..Fold::default() };
+
+    assert_eq!(vec![Empty, Folded("Ee".to_owned(), fold)], parse(r":m 'Éé'"));
+    assert_eq!(parse(r":m 'Éé'"), parse(r":ignoremark 'Ee'"));
+}
+#[test]
+fn scope() {
+    // An adverb lasts until the end of its group.
+    assert_eq!(vec![Group(vec![Empty, folded("a")], NonCapture), Char('b')], parse(r"[ :i a ] b"));
+    assert_eq!(vec![Empty, Empty, Char('a'), Empty, folded("b")], parse(r":i :!i a :i b"));
+}
+#[test]
+fn sigspace() {
+    let ws = parse(r"<.ws>").remove(0);
+
+    // Only whitespace after an atom counts.
+    assert_eq!(vec![Empty, Char('a'), ws.clone(), Char('b'), ws.clone()], parse(r":s  a b "));
+    assert_eq!(parse(r":s a b"), parse(r":sigspace a  b"));
+    assert_eq!(vec![Empty, Char('a'), Char('b')], parse(r":!s a b"));
+
+    // Whitespace before a quantifier doesn't so it still repeats the atom.
+    assert_eq!(vec![Empty, Repeat(Box::new(Char('a')), 1, None), ws.clone(), Char('b')],
+               parse(r":s a + b"));
+
+    // A comment doesn't end the whitespace.
+    assert_eq!(vec![Empty, Char('a'), Empty, ws.clone(), Char('b')], parse(":s a # x\n b"));
+
+    // Each branch ends with it but doesn't start with it.
+    let branches = vec![vec![Empty, Char('a'), ws.clone()], vec![Char('b')]];
+    assert_eq!(vec![Alternation(branches, Longest)], parse(r":s a | b"));
+}
+#[test]
+fn unknown() {
+    assert!(rules::parse::parse(r":x a").is_err());
+    assert!(rules::parse::parse(r"a :").is_err());
+}
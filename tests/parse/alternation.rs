@@ -0,0 +1,35 @@
+use rules::parse::Ast::*;
+use rules::parse::Choice::*;
+
+use super::parse;
+
+#[test]
+fn longest() {
+    assert_eq!(vec![Alternation(vec![vec![Char('a')], vec![Char('b'), Char('c')]], Longest)],
+               parse(r"a | bc"));
+}
+#[test]
+fn sequential() {
+    assert_eq!(vec![Alternation(vec![vec![Char('a')], vec![Char('b')]], Sequential)],
+               parse(r"a || b"));
+}
+#[test]
+fn precedence() {
+    // `||` binds looser than `|`.
+    let longest = Alternation(vec![vec![Char('a')], vec![Char('b')]], Longest);
+    assert_eq!(vec![Alternation(vec![vec![longest], vec![Char('c')]], Sequential)],
+               parse(r"a | b || c"));
+}
+#[test]
+fn leading() {
+    // A leading `|` or `||` is ignored.
+    assert_eq!(vec![Alternation(vec![vec![Char('a')], vec![Char('b')]], Longest)],
+               parse(r"| a | b"));
+    assert_eq!(vec![Alternation(vec![vec![Char('a')], vec![Char('b')]], Sequential)],
+               parse(r"|| a || b"));
+}
+#[test]
+fn empty_branch() {
+    assert!(rules::parse::parse(r"a | | b").is_err());
+    assert!(rules::parse::parse(r"a ||").is_err());
+}
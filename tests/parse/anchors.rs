@@ -0,0 +1,29 @@
+use rules::parse::Anchor::*;
+use rules::parse::Ast::*;
+
+use super::parse;
+
+#[test]
+fn string() {
+    assert_eq!(vec![Anchor(StartOfString), Char('a'), Anchor(EndOfString)], parse(r"^ a $"));
+}
+#[test]
+fn line() {
+    assert_eq!(vec![Anchor(StartOfLine), Char('a'), Anchor(EndOfLine)], parse(r"^^ a $$"));
+}
+#[test]
+fn named_capture_still_works() {
+    let named = NamedCapture("x".to_owned(), Box::new(Char('a')));
+    assert_eq!(vec![named, Anchor(EndOfString)], parse(r"$<x>=a $"));
+}
+#[test]
+fn word() {
+    assert_eq!(vec![Anchor(WordStart), Char('a'), Anchor(WordEnd)], parse(r"<< a >>"));
+    assert_eq!(vec![Anchor(WordStart), Char('a'), Anchor(WordEnd)], parse(r"« a »"));
+    assert_eq!(vec![Anchor(WordBoundary), Char('a')], parse(r"<wb> a"));
+}
+#[test]
+fn unknown_name() {
+    assert!(rules::parse::parse(r"<nope> a").is_err());
+    assert!(rules::parse::parse(r"<wb a").is_err());
+}
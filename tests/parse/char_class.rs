@@ -96,3 +96,50 @@ fn multiple_char_classes() {
                     Char('b'),
                     Class(deque_c)], parse(r"<[ a ]> b <[ c ]>"));
 }
+#[test]
+fn parens() {
+    // Set of chars inside `[]`
+    let set_a = vec![Char('a')].into();
+    let set_b = vec![Char('b')].into();
+    // A parenthesized part of a class is a nested `Class` with its own
+    // `Empty` inserted around ops.
+    let inner = new_deque(vec![Empty,
+                               Op(Difference),
+                               Set(set_b, Inclusive)]);
+    let deque = new_deque(vec![Set(set_a, Inclusive),
+                               Op(Intersection),
+                               Class(inner)]);
+
+    assert_eq!(vec![Class(deque)], parse(r"< [ a ] & ( - [ b ] ) >"));
+}
+#[test]
+fn parens_invalid() {
+    assert!(rules::parse::parse(r"< [ a ] & ( ) >").is_err());
+    assert!(rules::parse::parse(r"< [ a ] & ( [ b ] >").is_err());
+    assert!(rules::parse::parse(r"< [ a ] & [ b ] ) >").is_err());
+}
+#[test]
+fn ops_must_alternate() {
+    assert!(rules::parse::parse(r"<[a] [b]>").is_err());
+    assert!(rules::parse::parse(r"<[a] + + [b]>").is_err());
+    assert!(rules::parse::parse(r"<[a] + (- - [b])>").is_err());
+    assert!(rules::parse::parse(r"<alpha ([b])>").is_err());
+}
+#[test]
+fn escape_ellipsis() {
+    let class = |a, b| {
+        let set = vec![Range(range_set::Range(a, b))].into();
+
+        vec![Class(new_deque(vec![Set(set, Inclusive)]))]
+    };
+
+    assert_eq!(class('\t', 'a'), parse(r"< [ \t .. a ] >"));
+    assert_eq!(class('\t', '\r'), parse(r"< [ \t .. \r ] >"));
+    assert_eq!(class('\n', '\r'), parse(r"<[\n..\r]>"));
+    // Sets can't be endpoints.
+    assert!(rules::parse::parse(r"< [ \d .. z ] >").is_err());
+    assert!(rules::parse::parse(r"< [ a .. \d ] >").is_err());
+    // Nor can a range run backwards.
+    assert!(rules::parse::parse(r"< [ a .. \t ] >").is_err());
+    assert!(rules::parse::parse(r"< [ z .. a ] >").is_err());
+}
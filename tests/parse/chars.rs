@@ -30,3 +30,7 @@ fn escapes() {
     assert_eq!(vec![Set(PERLS.into(), Inclusive)], parse(r"\s"));
     assert_eq!(vec![Set(PERLS.into(), Exclusive)], parse(r"\S"));
 }
+#[test]
+fn newline() {
+    assert_eq!(vec![Char('a'), Newline, Char('b')], parse(r"a \n b"));
+}
@@ -0,0 +1,58 @@
+use rules::parse::Ast::*;
+use rules::parse::Membership::*;
+use rules::range_set;
+
+use super::{new_deque, parse};
+
+#[test]
+fn hex() {
+    assert_eq!(vec![Char('A'), Char('B')], parse(r"\x41 \x[42]"));
+    assert_eq!(vec![Char('☺')], parse(r"\x[263A]"));
+    assert_eq!(vec![Char('☺')], parse(r"\x[ 263a ]"));
+}
+#[test]
+fn octal() {
+    assert_eq!(vec![Char('A'), Char('A')], parse(r"\o101 \o[101]"));
+}
+#[test]
+fn control() {
+    assert_eq!(vec![Char('\u{0}'), Char('\u{1}'), Char('\u{1f}'), Char('\u{7f}')],
+               parse(r"\c@ \cA \c_ \c?"));
+    assert_eq!(vec![Char('a'), Char('a')], parse(r"\c97 \c[97]"));
+}
+#[test]
+fn named_chars() {
+    assert_eq!(vec![Char('\r'), Char('\u{c}'), Char('\u{1b}'), Char('\u{0}')],
+               parse(r"\r \f \e \0"));
+}
+#[test]
+fn punctuation() {
+    assert_eq!(vec![Char('.'), Char('\\'), Char(' ')], parse(r"\. \\ \ "));
+}
+#[test]
+fn in_ranges() {
+    let set = vec![Range(range_set::Range('A', 'Z'))].into();
+    let deque = new_deque(vec![Set(set, Inclusive)]);
+    assert_eq!(vec![Class(deque.clone())], parse(r"<[ \x41 .. \x[5A] ]>"));
+    assert_eq!(vec![Class(deque)], parse(r"<[ A .. \o132 ]>"));
+}
+#[test]
+fn invalid() {
+    assert!(rules::parse::parse(r"\q").is_err());
+    assert!(rules::parse::parse(r"\1").is_err());
+    assert!(rules::parse::parse(r"<[ \y ]>").is_err());
+    assert!(rules::parse::parse(r"\x").is_err());
+    assert!(rules::parse::parse(r"\x[zz]").is_err());
+    assert!(rules::parse::parse(r"\x[110000]").is_err());
+    assert!(rules::parse::parse(r"\x[41").is_err());
+    assert!(rules::parse::parse(r"\o9").is_err());
+    assert!(rules::parse::parse(r"\ca").is_err());
+    assert!(rules::parse::parse(r"<[ a .. \d ]>").is_err());
+}
+#[test]
+fn char_names() {
+    assert_eq!(vec![Char('α'), Char('á')],
+               parse(r"\c[GREEK SMALL LETTER ALPHA] \c[ latin small letter a with acute ]"));
+    assert_eq!(vec![Char('각')], parse(r"\c[HANGUL SYLLABLE GAG]"));
+    assert!(rules::parse::parse(r"\c[NOT A REAL NAME]").is_err());
+}
@@ -0,0 +1,54 @@
+use rules::parse::Ast::*;
+use rules::parse::Choice::*;
+use rules::parse::Faction::*;
+
+use super::parse;
+
+#[test]
+fn non_capture() {
+    assert_eq!(vec![Char('a'), Group(vec![Char('b'), Char('c')], NonCapture)],
+               parse(r"a [ b c ]"));
+}
+#[test]
+fn capture() {
+    assert_eq!(vec![Char('a'), Group(vec![Char('b'), Char('c')], Capture)],
+               parse(r"a ( b c )"));
+}
+#[test]
+fn nested() {
+    let inner = Group(vec![Char('b')], Capture);
+    assert_eq!(vec![Group(vec![Char('a'), inner], NonCapture)], parse(r"[ a ( b ) ]"));
+}
+#[test]
+fn alternation() {
+    let alternation = Alternation(vec![vec![Char('a')], vec![Char('b')]], Longest);
+    assert_eq!(vec![Group(vec![alternation], NonCapture), Char('c')], parse(r"[ a | b ] c"));
+}
+#[test]
+fn quantified() {
+    let group = Group(vec![Char('a'), Char('b')], NonCapture);
+    assert_eq!(vec![Repeat(Box::new(group), 1, None)], parse(r"[ab]+"));
+}
+#[test]
+fn invalid() {
+    assert!(rules::parse::parse(r"[ a").is_err());
+    assert!(rules::parse::parse(r"( a ]").is_err());
+    assert!(rules::parse::parse(r"a ]").is_err());
+    assert!(rules::parse::parse(r"[ ]").is_err());
+    assert!(rules::parse::parse(r"()").is_err());
+}
+#[test]
+fn named_capture() {
+    let group = Group(vec![Char('a')], Capture);
+    assert_eq!(vec![NamedCapture("x".to_owned(), Box::new(group))], parse(r"$<x>=(a)"));
+
+    let repeat = Repeat(Box::new(Char('a')), 1, None);
+    assert_eq!(vec![NamedCapture("x".to_owned(), Box::new(repeat))], parse(r"$<x> = a+"));
+}
+#[test]
+fn named_capture_invalid() {
+    assert!(rules::parse::parse(r"$<>=a").is_err());
+    assert!(rules::parse::parse(r"$<x y>=a").is_err());
+    assert!(rules::parse::parse(r"$<x> a").is_err());
+    assert!(rules::parse::parse(r"$<x>=").is_err());
+}
@@ -0,0 +1,43 @@
+use rules::parse::Ast::*;
+use rules::parse::Look::*;
+use rules::parse::Polarity::*;
+
+use rules::parse::{parse_with, Options};
+
+use super::parse;
+
+#[test]
+fn after() {
+    assert_eq!(vec![Char('a'), Lookaround(vec![Char('b')], Ahead, Positive)],
+               parse(r"a <?after b>"));
+    assert_eq!(vec![Char('a'), Lookaround(vec![Char('b')], Ahead, Negative)],
+               parse(r"a <!after b>"));
+}
+#[test]
+fn before() {
+    assert_eq!(vec![Lookaround(vec![Char('a')], Behind, Positive), Char('b')],
+               parse(r"<?before a> b"));
+    assert_eq!(vec![Lookaround(vec![Char('a')], Behind, Negative), Char('b')],
+               parse(r"<!before a> b"));
+}
+#[test]
+fn sub_pattern() {
+    let repeat = Repeat(Box::new(Char('a')), 1, None);
+    assert_eq!(vec![Lookaround(vec![repeat, Char('b')], Ahead, Positive)],
+               parse(r"<?after a+ b>"));
+}
+#[test]
+fn invalid() {
+    assert!(rules::parse::parse(r"<?after>").is_err());
+    assert!(rules::parse::parse(r"<?behind a>").is_err());
+    assert!(rules::parse::parse(r"<?after a").is_err());
+}
+#[test]
+fn raku_lookaround() {
+    let options = Options { raku_lookaround: true, ..Options::default() };
+
+    assert_eq!(vec![Char('a'), Lookaround(vec![Char('b')], Ahead, Positive)],
+               parse_with(r"a <?before b>", options).unwrap());
+    assert_eq!(vec![Lookaround(vec![Char('a')], Behind, Negative), Char('b')],
+               parse_with(r"<!after a> b", options).unwrap());
+}
@@ -10,5 +10,15 @@ fn parse(s: &str) -> Vec<Ast> {
     parse::parse(s).unwrap()
 }
 
+mod adverbs;
+mod alternation;
+mod anchors;
 mod chars;
+mod escapes;
 mod char_class;
+mod groups;
+mod lookaround;
+mod names;
+mod properties;
+mod quantifiers;
+mod words;
@@ -0,0 +1,27 @@
+use rules::parse::Ast::*;
+use rules::parse::Membership::*;
+use rules::unicode::general_category::Nd_table;
+
+use super::parse;
+
+#[test]
+fn capturing() {
+    let digit = Set(Nd_table.into(), Inclusive);
+    assert_eq!(vec![NamedCapture("digit".to_owned(), Box::new(digit))], parse(r"<digit>"));
+}
+#[test]
+fn non_capturing() {
+    assert_eq!(vec![Set(Nd_table.into(), Inclusive)], parse(r"<.digit>"));
+}
+#[test]
+fn unknown() {
+    assert!(rules::parse::parse(r"<alphabet>").is_err());
+    assert!(rules::parse::parse(r"<.>").is_err());
+    assert!(rules::parse::parse(r"<.alpha").is_err());
+}
+#[test]
+fn class_operands() {
+    assert_eq!(parse(r"<:Nd - [0]>"), parse(r"<digit - [0]>"));
+    assert!(rules::parse::parse(r"<ident + [x]>").is_err());
+    assert!(rules::parse::parse(r"<+alphabet>").is_err());
+}
@@ -0,0 +1,42 @@
+use rules::parse::Ast::*;
+use rules::parse::Membership::*;
+use rules::unicode::general_category::{L_table, Lu_table};
+use rules::unicode::script::Greek_table;
+
+use super::{new_deque, parse};
+
+fn class(ast: rules::parse::Ast) -> Vec<rules::parse::Ast> {
+    vec![Class(new_deque(vec![ast]))]
+}
+
+#[test]
+fn short_name() {
+    assert_eq!(class(Set(Lu_table.into(), Inclusive)), parse(r"<:Lu>"));
+    assert_eq!(class(Set(Greek_table.into(), Inclusive)), parse(r"<:Greek>"));
+}
+#[test]
+fn negated() {
+    assert_eq!(class(Set(L_table.into(), Exclusive)), parse(r"<:!L>"));
+}
+#[test]
+fn qualified() {
+    assert_eq!(class(Set(Greek_table.into(), Inclusive)), parse(r"<:Script<Greek>>"));
+    assert_eq!(class(Set(Greek_table.into(), Inclusive)), parse(r"<:sc<Greek>>"));
+    assert_eq!(class(Set(Lu_table.into(), Inclusive)), parse(r"<:General_Category<Lu>>"));
+    assert_eq!(class(Set(Lu_table.into(), Inclusive)), parse(r"<:gc<Uppercase_Letter>>"));
+}
+#[test]
+fn loose_matching() {
+    assert_eq!(class(Set(Lu_table.into(), Inclusive)), parse(r"<:uppercase-letter>"));
+    assert_eq!(class(Set(Lu_table.into(), Inclusive)), parse(r"<:IsLu>"));
+    assert_eq!(class(Set(Greek_table.into(), Inclusive)), parse(r"<:script<GREEK>>"));
+}
+#[test]
+fn unknown() {
+    assert!(rules::parse::parse(r"<:Nope>").is_err());
+    // `Lu` is a general category, not a script.
+    assert!(rules::parse::parse(r"<:Script<Lu>>").is_err());
+    assert!(rules::parse::parse(r"<:gc<Greek>>").is_err());
+    assert!(rules::parse::parse(r"<:Nope<Greek>>").is_err());
+    assert!(rules::parse::parse(r"<:Lu").is_err());
+}
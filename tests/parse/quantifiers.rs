@@ -0,0 +1,30 @@
+use rules::parse::Ast::*;
+
+use super::parse;
+
+fn repeat(c: char, min: usize, max: Option<usize>) -> rules::parse::Ast {
+    Repeat(Box::new(Char(c)), min, max)
+}
+
+#[test]
+fn symbols() {
+    assert_eq!(vec![Char('a'), repeat('b', 0, None)],    parse(r"ab*"));
+    assert_eq!(vec![Char('a'), repeat('b', 1, None)],    parse(r"ab+"));
+    assert_eq!(vec![Char('a'), repeat('b', 0, Some(1))], parse(r"ab?"));
+}
+#[test]
+fn repeat_counts() {
+    assert_eq!(vec![repeat('a', 3, Some(3))], parse(r"a ** 3"));
+    assert_eq!(vec![repeat('a', 2, Some(5))], parse(r"a**2..5"));
+    assert_eq!(vec![repeat('a', 2, Some(5))], parse(r"a ** 2 .. 5"));
+    assert_eq!(vec![repeat('a', 2, None)],    parse(r"a ** 2..*"));
+    assert_eq!(vec![repeat('a', 12, Some(12)), Char('b')], parse(r"a ** 12 b"));
+}
+#[test]
+fn invalid() {
+    assert!(rules::parse::parse(r"+a").is_err());
+    assert!(rules::parse::parse(r"a+*").is_err());
+    assert!(rules::parse::parse(r"a **").is_err());
+    assert!(rules::parse::parse(r"a ** b").is_err());
+    assert!(rules::parse::parse(r"a ** 5..2").is_err());
+}
@@ -0,0 +1,45 @@
+use rules::parse::Ast::*;
+use rules::parse::Choice::*;
+
+use super::parse;
+
+#[test]
+fn word_list() {
+    let branches = vec![vec![Literal("big".to_owned())],
+                        vec![Literal("small".to_owned())],
+                        vec![Literal("x-large".to_owned())]];
+
+    assert_eq!(vec![Alternation(branches, Longest)], parse(r"< big small   x-large >"));
+}
+#[test]
+fn single_word() {
+    assert_eq!(vec![Literal("big".to_owned())], parse(r"< big>"));
+}
+#[test]
+fn class_operands() {
+    // A name followed by a set operator starts a class instead.
+    assert_eq!(parse(r"<alpha + [_]>"), parse(r"< alpha + [_] >"));
+    assert_eq!(parse(r"<digit - [0]>"), parse(r"<  digit -[0] >"));
+    assert_eq!(parse(r"<alpha + digit>"), parse(r"< alpha + digit >"));
+
+    let branches = vec![vec![Literal("alpha".to_owned())], vec![Literal("digit".to_owned())]];
+    assert_eq!(vec![Alternation(branches, Longest)], parse(r"< alpha digit >"));
+}
+#[test]
+fn hyphenated() {
+    // A name with a `-` right after it is only a word.
+    let words = |words: &[&str]| {
+        let branches = words.iter().map(|&word| vec![Literal(word.to_owned())]).collect();
+        vec![Alternation(branches, Longest)]
+    };
+
+    assert_eq!(words(&["upper-deck", "lower-deck"]), parse(r"< upper-deck lower-deck >"));
+    assert_eq!(words(&["print-ready", "other"]), parse(r"< print-ready other >"));
+    assert_eq!(vec![Literal("space-saver".to_owned())], parse(r"< space-saver >"));
+    // Without another operand after it the `-` is a word too.
+    assert_eq!(words(&["alpha", "-", "beta"]), parse(r"< alpha - beta >"));
+}
+#[test]
+fn invalid() {
+    assert!(rules::parse::parse(r"< big small").is_err());
+}
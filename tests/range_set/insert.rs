@@ -57,3 +57,18 @@ fn disjoint_extend() {
 fn panic_on_decreasing_order() {
     generate(vec![('5', '3')]);
 }
+#[test]
+fn around_surrogates() {
+    // Nothing lies between these two so they join.
+    let set = generate(vec![('a', '\u{d7ff}'), ('\u{e000}', '\u{e001}')]);
+
+    let other = generate(vec![('a', '\u{e001}')]);
+    assert_eq!(set, other);
+}
+#[test]
+fn last_char() {
+    let set = generate(vec![('\u{10fffe}', '\u{10ffff}'), ('\u{0}', '\u{1}'), ('\u{10fff0}', '\u{10ffff}')]);
+
+    let other = generate(vec![('\u{0}', '\u{1}'), ('\u{10fff0}', '\u{10ffff}')]);
+    assert_eq!(set, other);
+}
@@ -54,3 +54,20 @@ fn disjoint() {
     let other = generate(vec![('3', '4')]);
     assert_eq!(set, other);
 }
+#[test]
+fn subset_keeps_later_ranges() {
+    let mut set = generate(vec![('1', '5'), ('7', '8')]);
+    set.remove(Range('3', '3'));
+
+    let other = generate(vec![('1', '2'), ('4', '5'), ('7', '8')]);
+    assert_eq!(set, other);
+}
+#[test]
+fn around_surrogates() {
+    let mut set = generate(vec![('\u{d000}', '\u{f000}')]);
+    set.remove(Range('\u{d100}', '\u{d7ff}'));
+    set.remove(Range('\u{e000}', '\u{e100}'));
+
+    let other = generate(vec![('\u{d000}', '\u{d0ff}'), ('\u{e101}', '\u{f000}')]);
+    assert_eq!(set, other);
+}
@@ -0,0 +1,111 @@
+use rules::re::Regex;
+
+#[test]
+fn positional() {
+    let re = Regex::new(r"(\w+) '=' (\d+)");
+    let caps = re.captures("set width=42;").unwrap();
+
+    assert_eq!(2, caps.len());
+    assert_eq!("width", &caps[0]);
+    assert_eq!("42", &caps[1]);
+    assert_eq!("width=42", caps.whole().as_str());
+
+    let second = caps.get(1).unwrap();
+    assert_eq!((10, 12), (second.start(), second.end()));
+
+    assert!(re.captures("no numbers").is_none());
+}
+#[test]
+fn not_participating() {
+    let re = Regex::new(r"(a) [ (b) || (c) ]");
+    let caps = re.captures("ac").unwrap();
+
+    assert_eq!("a", &caps[0]);
+    // Each branch numbers from `$1`.
+    assert_eq!("c", &caps[1]);
+    assert_eq!(2, caps.len());
+
+    let re = Regex::new(r"(a) (x)? (b)");
+    let caps = re.captures("ab").unwrap();
+    assert!(caps.get(1).is_none());
+    assert_eq!("b", &caps[2]);
+    assert!(caps.get(3).is_none());
+}
+#[test]
+fn nested_belongs_to_outer() {
+    let re = Regex::new(r"( a (b) ) (c)");
+    let caps = re.captures("abc").unwrap();
+
+    assert_eq!(2, caps.len());
+    assert_eq!("ab", &caps[0]);
+    assert_eq!("c", &caps[1]);
+}
+#[test]
+fn quantified() {
+    // Only the last repetition is kept.
+    let re = Regex::new(r"[ (\d) ',' ]+ (x)");
+    let caps = re.captures("1,2,3,x").unwrap();
+
+    assert_eq!(2, caps.len());
+    assert_eq!("3", &caps[0]);
+    assert_eq!("x", &caps[1]);
+}
+#[test]
+fn backtracking_resets() {
+    // The first attempt captures `ab` before failing and backtracking.
+    let re = Regex::new(r"[ (ab) c || a (b) d ]");
+    let caps = re.captures("abd").unwrap();
+    assert_eq!("b", &caps[0]);
+
+    let re = Regex::new(r"[ (a) x ]? (a) y");
+    let caps = re.captures("ay").unwrap();
+    assert!(caps.get(0).is_none());
+    assert_eq!("a", &caps[1]);
+}
+#[test]
+#[should_panic]
+fn index_missing() {
+    let re = Regex::new(r"(a) (x)?");
+    let caps = re.captures("a").unwrap();
+    let _ = &caps[1];
+}
+#[test]
+fn named() {
+    let re = Regex::new(r"$<year>=[\d**4] '-' $<month>=(\d**2) '-' (\d**2)");
+    let caps = re.captures("on 2015-06-30").unwrap();
+
+    assert_eq!("2015", caps.name("year").unwrap().as_str());
+    assert_eq!("06", caps.name("month").unwrap().as_str());
+    // A named `( )` doesn't take a number.
+    assert_eq!(1, caps.len());
+    assert_eq!("30", &caps[0]);
+    assert!(caps.name("day").is_none());
+}
+#[test]
+fn named_quantified() {
+    let re = Regex::new(r"$<num> = \d+ ' ' $<word>=<[a..z]>+");
+    let caps = re.captures("x 123 abc").unwrap();
+
+    assert_eq!("123", caps.name("num").unwrap().as_str());
+    assert_eq!("abc", caps.name("word").unwrap().as_str());
+}
+#[test]
+fn named_in_alternation() {
+    let re = Regex::new(r"$<n>=\d+ || $<w>=\w+");
+    let caps = re.captures("abc").unwrap();
+
+    assert!(caps.name("n").is_none());
+    assert_eq!("abc", caps.name("w").unwrap().as_str());
+
+    // The same name in two branches shares one capture.
+    let re = Regex::new(r"$<v>=\d+ | $<v>=<[a..z]>+");
+    assert_eq!("abc", re.captures("abc").unwrap().name("v").unwrap().as_str());
+    assert_eq!(["v"], re.capture_names());
+}
+#[test]
+fn capture_names() {
+    let re = Regex::new(r"$<a>=x (y) $<b>=[z] $<a>=x");
+    assert_eq!(["a", "b"], re.capture_names());
+
+    assert!(Regex::new(r"(x)").capture_names().is_empty());
+}
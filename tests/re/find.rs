@@ -0,0 +1,24 @@
+use rules::re::Regex;
+
+#[test]
+fn span() {
+    let re = Regex::new(r"\d+");
+    let found = re.find("abc 123 def").unwrap();
+    assert_eq!((4, 7), (found.start(), found.end()));
+    assert_eq!("123", found.as_str());
+
+    assert!(re.find("abc def").is_none());
+}
+#[test]
+fn leftmost() {
+    let re = Regex::new(r"'cat' | 'dog'");
+    assert_eq!("dog", re.find("hotdog and a cat").unwrap().as_str());
+}
+#[test]
+fn multibyte_offsets() {
+    // Offsets are in bytes.
+    let re = Regex::new(r"ん+");
+    let found = re.find("こんんに").unwrap();
+    assert_eq!((3, 9), (found.start(), found.end()));
+    assert_eq!("んん", found.as_str());
+}
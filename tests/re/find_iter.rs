@@ -0,0 +1,39 @@
+use rules::re::Regex;
+
+fn find_all<'t>(re: &str, txt: &'t str) -> Vec<&'t str> {
+    Regex::new(re).find_iter(txt)
+                  .map(|m| m.as_str())
+                  .collect()
+}
+
+#[test]
+fn non_overlapping() {
+    assert_eq!(vec!["12", "345", "6"], find_all(r"\d+", "a12b345c6"));
+    assert_eq!(vec!["aa", "aa"], find_all(r"aa", "aaaaa"));
+    assert!(find_all(r"\d+", "abc").is_empty());
+}
+#[test]
+fn spans() {
+    let spans: Vec<(usize, usize)> = Regex::new(r"'ab'").find_iter("ab ab")
+                                                        .map(|m| (m.start(), m.end()))
+                                                        .collect();
+    assert_eq!(vec![(0, 2), (3, 5)], spans);
+}
+#[test]
+fn empty_matches() {
+    // An empty match never overlaps the end of the match before it.
+    assert_eq!(vec!["", "aa", ""], find_all(r"a*", "baab"));
+    assert_eq!(vec!["", "", ""], find_all(r"x?", "こん"));
+}
+#[test]
+fn long_text() {
+    // Far more positions than the search keeps track of at once.
+    let txt = "key=1 foo bar\n".repeat(1000);
+    let found = find_all(r"<ident> ** 1..20 '=' \d+", &txt);
+    assert_eq!(1000, found.len());
+    assert!(found.iter().all(|&m| m == "key=1"));
+
+    let txt = "a".repeat(100_000) + "b";
+    assert_eq!(vec![&txt[..]], find_all(r".* b", &txt));
+    assert!(find_all(r".* c", &txt).is_empty());
+}
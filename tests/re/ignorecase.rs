@@ -0,0 +1,76 @@
+use rules::re::{Regex, RegexBuilder};
+
+#[test]
+fn literals() {
+    let re = Regex::new(r":i 'hello' \s world");
+    assert!( re.is_match("HeLLo WORLD"));
+    assert!(!re.is_match("HeLLo WORD"));
+}
+#[test]
+fn offsets() {
+    // The text is folded as it's matched so offsets point into the original.
+    let re = Regex::new(r":i 'kσ'");
+    let found = re.find("ab\u{212a}Σ").unwrap();
+    assert_eq!((2, 7), (found.start(), found.end()));
+    assert_eq!("\u{212a}Σ", found.as_str());
+}
+#[test]
+fn sets() {
+    let re = Regex::new(r"^ :i <[a..f]>+ $");
+    assert!( re.is_match("abcABCdef"));
+    assert!(!re.is_match("abcg"));
+
+    let re = Regex::new(r"^ :i <-[a]>+ $");
+    assert!( re.is_match("bcd"));
+    assert!(!re.is_match("bAd"));
+
+    assert!(Regex::new(r":i <:Lu>").is_match("abc"));
+}
+#[test]
+fn scope() {
+    let re = Regex::new(r"^ [ :i a ] b $");
+    assert!( re.is_match("Ab"));
+    assert!(!re.is_match("AB"));
+
+    let re = Regex::new(r"^ :i a :!i b $");
+    assert!( re.is_match("Ab"));
+    assert!(!re.is_match("aB"));
+}
+#[test]
+fn words() {
+    let re = Regex::new(r":i < apple pineapple >");
+    assert_eq!("PineApple", re.find("a PineApple").unwrap().as_str());
+}
+#[test]
+fn builder() {
+    let re = RegexBuilder::new(r"^ 'abc' $").ignorecase(true).build();
+    assert!(re.is_match("ABC"));
+
+    let re = RegexBuilder::new(r"^ :!i 'abc' $").ignorecase(true).build();
+    assert!(!re.is_match("ABC"));
+}
+#[test]
+fn full_folding() {
+    let re = Regex::new(r":i 'strasse'");
+    let found = re.find("Die Straße").unwrap();
+    assert_eq!((4, 11), (found.start(), found.end()));
+
+    assert!(Regex::new(r":i ß").is_match("SS"));
+    assert!(Regex::new(r":i 'FILE'").is_match("\u{fb01}le"));
+    assert!(Regex::new(r":i 'İstanbul'").is_match("i\u{307}stanbul"));
+}
+#[test]
+fn full_folding_whole_chars() {
+    // Half of the `ss` which `ß` folds to can't be matched on its own.
+    assert!(!Regex::new(r"^ :i s $").is_match("ß"));
+    assert!(!Regex::new(r"^ :i 'ss' s $").is_match("sß"));
+    assert_eq!("ß", Regex::new(r":i 'ss'").find("sß").unwrap().as_str());
+}
+#[test]
+fn full_folding_words() {
+    let re = Regex::new(r":i < strasse weg >");
+    assert_eq!("STRAßE", re.find("die STRAßE").unwrap().as_str());
+
+    // `ß` can start a word since it folds to `ss`.
+    assert_eq!("ßh", Regex::new(r":i < ssh >").find("aßh").unwrap().as_str());
+}
@@ -0,0 +1,55 @@
+use rules::re::{Regex, RegexBuilder};
+
+#[test]
+fn literals() {
+    let re = Regex::new(r":m 'Jose'");
+    assert_eq!("José", re.find("Hola José!").unwrap().as_str());
+    assert!(Regex::new(r":m 'José'").is_match("Jose"));
+
+    let re = Regex::new(r"^ :m e $");
+    assert!( re.is_match("é"));
+    assert!( re.is_match("ê"));
+    assert!( re.is_match("e\u{301}"));
+    assert!(!re.is_match("a"));
+}
+#[test]
+fn offsets() {
+    // Marks after a char are part of it.
+    let re = Regex::new(r":m 'Jose'");
+    let found = re.find("Jose\u{301}!").unwrap();
+    assert_eq!((0, 6), (found.start(), found.end()));
+
+    // A match doesn't start on a mark left over from the char before.
+    let found = Regex::new(r":m e").find("\u{301}e").unwrap();
+    assert_eq!((2, 3), (found.start(), found.end()));
+}
+#[test]
+fn with_ignorecase() {
+    assert!(Regex::new(r":i :m 'jose'").is_match("JOSÉ"));
+    assert!(!Regex::new(r":m 'jose'").is_match("JOSÉ"));
+}
+#[test]
+fn sets() {
+    let re = Regex::new(r"^ :m <[a..z]>+ $");
+    assert!( re.is_match("café"));
+    assert!(!re.is_match("cafe!"));
+    // The marks after a decomposed char go with it.
+    assert!( re.is_match("cafe\u{301}"));
+    assert!( re.is_match("cafe\u{302}\u{301}"));
+
+    let found = Regex::new(r":m <[e]>").find("e\u{301}x").unwrap();
+    assert_eq!((0, 3), (found.start(), found.end()));
+    let found = Regex::new(r":m \w+").find("ne\u{301}e!").unwrap();
+    assert_eq!((0, 5), (found.start(), found.end()));
+    assert!(Regex::new(r"^ :m <alpha>+ $").is_match("Jose\u{301}"));
+}
+#[test]
+fn words() {
+    let re = Regex::new(r":m < Jose Maria >");
+    assert_eq!("María", re.find("Sí, María").unwrap().as_str());
+}
+#[test]
+fn builder() {
+    let re = RegexBuilder::new(r"^ 'cafe' $").ignoremark(true).build();
+    assert!(re.is_match("café"));
+}
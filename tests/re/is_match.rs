@@ -1,4 +1,4 @@
-use rules::re::Regex;
+use rules::re::{Regex, RegexBuilder};
 
 #[test]
 fn whitespace_ignored() {
@@ -99,3 +99,213 @@ fn comments_are_ignored() {
     assert!(after_both.is_match("pineapple"));
 }
 
+#[test]
+fn quantifiers() {
+    let re = Regex::new(r"\d+");
+    assert!( re.is_match("abc 123"));
+    assert!(!re.is_match("abc"));
+
+    let re = Regex::new(r"colou?r");
+    assert!( re.is_match("color"));
+    assert!( re.is_match("colour"));
+    assert!(!re.is_match("colouur"));
+
+    let re = Regex::new(r"a <[ b ]>* c");
+    assert!( re.is_match("ac"));
+    assert!( re.is_match("abbbc"));
+    assert!(!re.is_match("abd"));
+
+    let re = Regex::new(r"x \d ** 2..3 x");
+    assert!(!re.is_match("x1x"));
+    assert!( re.is_match("x12x"));
+    assert!( re.is_match("x123x"));
+    assert!(!re.is_match("x1234x"));
+
+    let re = Regex::new(r"x \d ** 2 x");
+    assert!( re.is_match("x12x"));
+    assert!(!re.is_match("x123x"));
+
+    let re = Regex::new(r"x 'ab' ** 2..* x");
+    assert!(!re.is_match("xabx"));
+    assert!( re.is_match("xababababx"));
+}
+#[test]
+fn backtracking() {
+    // `\d+` has to give back the `5` for the literal to match.
+    let re = Regex::new(r"\d+ 5");
+    assert!( re.is_match("12345"));
+    assert!(!re.is_match("12346"));
+
+    let re = Regex::new(r"a* ab");
+    assert!( re.is_match("aaab"));
+
+    let re = Regex::new(r"x <[ ab ]>* b x");
+    assert!( re.is_match("xababbx"));
+    assert!(!re.is_match("xababax"));
+
+    let re = Regex::new(r"\w ** 2..4 c");
+    assert!( re.is_match("abcc"));
+    assert!(!re.is_match("ac"));
+}
+#[test]
+fn multibyte() {
+    let re = Regex::new(r"こ+ ん");
+    assert!( re.is_match("ここん"));
+    assert!(!re.is_match("ここに"));
+
+    let re = Regex::new(r"<[ a .. z ]> 'に'");
+    assert!( re.is_match("こんaに"));
+    assert!(!re.is_match("こんにち"));
+}
+#[test]
+fn alternation() {
+    let re = Regex::new(r"'cat' | 'dog'");
+    assert!( re.is_match("hotdog"));
+    assert!( re.is_match("catalog"));
+    assert!(!re.is_match("cow"));
+
+    let re = Regex::new(r"'cat' || 'dog' || \d+");
+    assert!( re.is_match("dog"));
+    assert!( re.is_match("42"));
+    assert!(!re.is_match("cow"));
+}
+#[test]
+fn groups() {
+    let re = Regex::new(r"'<' [ 'b' || 'i' ] '>'");
+    assert!( re.is_match("<b>"));
+    assert!( re.is_match("<i>"));
+    assert!(!re.is_match("<u>"));
+
+    let re = Regex::new(r"x ( \d ** 1..3 ) ** 2 x");
+    assert!( re.is_match("x1234x"));
+    assert!(!re.is_match("x1234567x"));
+
+    let re = Regex::new(r"[ 'na' ]+ ' batman'");
+    assert!( re.is_match("nanana batman"));
+    assert!(!re.is_match("n batman"));
+}
+#[test]
+fn lookaround() {
+    // An identifier which isn't followed by `(`.
+    let re = Regex::new(r"\w+ <!after '('> ' '");
+    assert!( re.is_match("foo bar"));
+    assert!(!re.is_match("foo("));
+
+    let re = Regex::new(r"<?before [ 'é' | 'e' ]+> \d");
+    assert!( re.is_match("éée1"));
+    assert!(!re.is_match("x1"));
+}
+#[test]
+fn raku_lookaround() {
+    let re = RegexBuilder::new(r"<?after foo> bar <?before baz>").raku_lookaround(true).build();
+    assert!( re.is_match("foobarbaz"));
+    assert!(!re.is_match("bazbarfoo"));
+
+    // The default is unchanged.
+    let re = RegexBuilder::new(r"<?before foo> bar <?after baz>").build();
+    assert!( re.is_match("foobarbaz"));
+}
+#[test]
+fn anchors() {
+    // A whole identifier.
+    let re = Regex::new(r"^ <[ a .. z _ ]> \w* $");
+    assert!( re.is_match("snake_case"));
+    assert!(!re.is_match("kebab-case"));
+    assert!(!re.is_match("1st"));
+
+    let re = Regex::new(r"^^ '#'");
+    assert!( re.is_match("code\n# comment"));
+    assert!(!re.is_match("code # comment"));
+}
+#[test]
+fn word_boundaries() {
+    let re = Regex::new(r"<< 'if' >>");
+    assert!( re.is_match("x if y"));
+    assert!( re.is_match("(if)"));
+    assert!(!re.is_match("iffy"));
+    assert!(!re.is_match("elif"));
+}
+#[test]
+fn dot() {
+    let re = Regex::new(r"apples . oranges");
+    assert!( re.is_match("apples_oranges"));
+    assert!( re.is_match("apples\noranges"));
+    assert!(!re.is_match("applesoranges"));
+
+    let re = Regex::new(r"^ .+ $");
+    assert!( re.is_match("any thing"));
+    assert!(!re.is_match(""));
+}
+#[test]
+fn horizontal_vertical() {
+    // Indentation but no line break.
+    let re = Regex::new(r"^ \h+ \H");
+    assert!( re.is_match("\t  code"));
+    assert!( re.is_match("\u{a0}\u{3000}x"));
+    assert!(!re.is_match("\n  code"));
+
+    let re = Regex::new(r"\v");
+    assert!( re.is_match("a\r\nb"));
+    assert!( re.is_match("a\u{2028}b"));
+    assert!(!re.is_match("a \tb"));
+
+    let re = Regex::new(r"x \V x");
+    assert!( re.is_match("x x"));
+    assert!(!re.is_match("x\u{b}x"));
+}
+#[test]
+fn horizontal_vertical_in_class() {
+    let re = Regex::new(r"^ <[ \h , ]>+ $");
+    assert!( re.is_match(" ,\t, "));
+    assert!(!re.is_match(" ,\n"));
+
+    let re = Regex::new(r"<[ \v a ]>");
+    assert!( re.is_match("\u{85}"));
+    assert!( re.is_match("a"));
+    assert!(!re.is_match(" "));
+}
+#[test]
+fn logical_newline() {
+    // Windows and Unix files alike.
+    let re = Regex::new(r"'end' \n 'start'");
+    assert!( re.is_match("end\nstart"));
+    assert!( re.is_match("end\r\nstart"));
+    assert!(!re.is_match("end start"));
+
+    let re = Regex::new(r"^ \N+ $");
+    assert!( re.is_match("one line"));
+    assert!(!re.is_match("two\r\nlines"));
+
+    let re = Regex::new(r"x <[ \n ]> x");
+    assert!( re.is_match("x\rx"));
+    assert!(!re.is_match("x\r\nx"));
+}
+#[test]
+fn char_escapes() {
+    let re = Regex::new(r"\x41 \x[263A] \o[102]");
+    assert!( re.is_match("A☺B"));
+    assert!(!re.is_match("x41"));
+
+    let re = Regex::new(r"'a' \r \n");
+    assert!( re.is_match("a\r\r\n"));
+    assert!(!re.is_match("a\n"));
+
+    let re = Regex::new(r"<[ \c@ .. \c_ ]>");
+    assert!( re.is_match("\u{1b}"));
+    assert!(!re.is_match(" "));
+
+    // Single char escapes can end either side of a range.
+    let re = Regex::new(r"<[ \x[1] .. \t ]>");
+    assert!( re.is_match("\t"));
+    assert!(!re.is_match("\n"));
+
+    let re = Regex::new(r"^ <[ \t .. \r ]>+ $");
+    assert!( re.is_match("\t\n\u{b}\r"));
+    assert!(!re.is_match("\t "));
+}
+#[test]
+fn char_names() {
+    let re = Regex::new(r"<[ \c[GREEK SMALL LETTER ALPHA] .. \c[GREEK SMALL LETTER OMEGA] ]>+");
+    assert!( re.is_match("λόγος"));
+    assert!(!re.is_match("logos"));
+}
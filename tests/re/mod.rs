@@ -1 +1,9 @@
+mod captures;
+mod find;
+mod find_iter;
+mod ignorecase;
+mod ignoremark;
 mod is_match;
+mod named_classes;
+mod sigspace;
+mod words;
@@ -0,0 +1,92 @@
+use rules::re::Regex;
+
+fn matches_all(re: &str, txt: &str) -> bool {
+    let re = Regex::new(&format!("^ {}+ $", re));
+    re.is_match(txt)
+}
+
+#[test]
+fn letters() {
+    assert!( matches_all("<.alpha>", "aΩ_é"));
+    assert!(!matches_all("<.alpha>", "a1"));
+    assert!( matches_all("<.upper>", "ABΩ"));
+    assert!(!matches_all("<.upper>", "Ab"));
+    assert!( matches_all("<.lower>", "abω"));
+    assert!(!matches_all("<.lower>", "aB"));
+    assert!( matches_all("<.alnum>", "a1_٣"));
+    assert!(!matches_all("<.alnum>", "a-1"));
+}
+#[test]
+fn digits() {
+    assert!( matches_all("<.digit>", "0123٣"));
+    assert!(!matches_all("<.digit>", "1a"));
+    assert!( matches_all("<.xdigit>", "09afAF"));
+    assert!(!matches_all("<.xdigit>", "g"));
+}
+#[test]
+fn spaces() {
+    assert!( matches_all("<.space>", " \t\n\u{2028}"));
+    assert!( matches_all("<.blank>", " \t\u{a0}"));
+    assert!(!matches_all("<.blank>", "\n"));
+}
+#[test]
+fn others() {
+    assert!( matches_all("<.cntrl>", "\u{0}\u{1b}\u{7f}"));
+    assert!(!matches_all("<.cntrl>", "a"));
+    assert!( matches_all("<.punct>", "!,.?«»$+"));
+    // Symbols outside of ASCII aren't punctuation.
+    assert!(!matches_all("<.punct>", "€"));
+    assert!( matches_all("<.graph>", "a!€"));
+    assert!(!matches_all("<.graph>", "a b"));
+    assert!( matches_all("<.print>", "a b€"));
+    assert!(!matches_all("<.print>", "a\tb"));
+}
+#[test]
+fn ident() {
+    let re = Regex::new(r"^ <.ident> $");
+    assert!( re.is_match("snake_case1"));
+    assert!( re.is_match("_private"));
+    assert!(!re.is_match("1st"));
+}
+#[test]
+fn ws() {
+    // Whitespace is optional except between two words.
+    let re = Regex::new(r"^ <.ws> 'a' <.ws> '=' <.ws> \w+ <.ws> $");
+    assert!( re.is_match("a = b"));
+    assert!( re.is_match("a=b"));
+    assert!( re.is_match("  a =b  "));
+
+    let re = Regex::new(r"^ 'let' <.ws> 'a' $");
+    assert!( re.is_match("let a"));
+    assert!(!re.is_match("leta"));
+}
+#[test]
+fn captures() {
+    let re = Regex::new(r"<ident> <.ws> '=' <.ws> <digit>+");
+    let caps = re.captures("x = 42").unwrap();
+
+    assert_eq!(caps.name("ident").unwrap().as_str(), "x");
+    assert_eq!(caps.name("digit").unwrap().as_str(), "42");
+    assert_eq!(re.capture_names(), ["ident", "digit"]);
+}
+#[test]
+fn properties() {
+    assert!( matches_all("<:Lu>", "ABΩ"));
+    assert!(!matches_all("<:Lu>", "Ab"));
+    assert!( matches_all("<:!L>", "1 !"));
+    assert!(!matches_all("<:!L>", "1a"));
+    assert!( matches_all("<:Script<Greek>>", "λόγος"));
+    assert!(!matches_all("<:Script<Greek>>", "logos"));
+    assert!( matches_all("<:Alphabetic>", "aé"));
+}
+#[test]
+fn set_operators() {
+    assert!( matches_all("<:L - [a..z]>", "ABΩé"));
+    assert!(!matches_all("<:L - [a..z]>", "Ab"));
+    assert!( matches_all("<alpha + [_-]>", "a_-b"));
+    assert!(!matches_all("<alpha + [_-]>", "a1"));
+    assert!( matches_all("<+digit -[0]>", "19٣"));
+    assert!(!matches_all("<+digit -[0]>", "10"));
+    assert!( matches_all("<:!L & [\\S]>", "1!"));
+    assert!(!matches_all("<:!L & [\\S]>", "1 a"));
+}
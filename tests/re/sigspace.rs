@@ -0,0 +1,40 @@
+use rules::re::{Regex, RegexBuilder};
+
+#[test]
+fn config() {
+    let re = Regex::new(r":s ^ <ident> '=' \d+ $");
+    assert!( re.is_match("size=10"));
+    assert!( re.is_match("  size = 10  "));
+    assert!(!re.is_match("si ze = 10"));
+    assert!(!re.is_match("size = 1 0"));
+
+    let caps = Regex::new(r":s ^ (<.ident>) '=' (\w+) $").captures("  name =  value ").unwrap();
+    assert_eq!("name", &caps[0]);
+    assert_eq!("value", &caps[1]);
+}
+#[test]
+fn words() {
+    // Whitespace is needed between two word chars but nowhere else.
+    let re = Regex::new(r":s ^ 'key' 'value' $");
+    assert!( re.is_match("key value"));
+    assert!( re.is_match("key \t\n value"));
+    assert!(!re.is_match("keyvalue"));
+
+    let re = Regex::new(r":s ^ a+ '!' $");
+    assert!(re.is_match("aaa!"));
+    assert!(re.is_match("aaa !"));
+}
+#[test]
+fn scope() {
+    // The space before `]` is after an atom too so `b` can't touch `c`.
+    let re = Regex::new(r"^ [ :s a b] c d $");
+    assert!( re.is_match("a bcd"));
+    assert!(!re.is_match("a bc d"));
+    assert!(!Regex::new(r"^ [ :s a b ] c d $").is_match("a bcd"));
+}
+#[test]
+fn builder() {
+    let re = RegexBuilder::new(r"^ a b $").sigspace(true).build();
+    assert!(re.is_match("a b"));
+    assert!(!Regex::new(r"^ a b $").is_match("a b"));
+}
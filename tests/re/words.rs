@@ -0,0 +1,39 @@
+use rules::re::Regex;
+
+#[test]
+fn longest_word() {
+    let re = Regex::new(r"< a abc ab >");
+    assert_eq!("abc", re.find("xabcd").unwrap().as_str());
+    assert_eq!("ab", re.find("xabd").unwrap().as_str());
+}
+#[test]
+fn backtracks_to_shorter() {
+    // `abc` leaves nothing for `c` so `ab` is tried next.
+    let re = Regex::new(r"< a ab abc > c");
+    assert_eq!("abc", re.find("abc").unwrap().as_str());
+    assert!(!re.is_match("ab"));
+}
+#[test]
+fn in_sequence() {
+    let re = Regex::new(r"^ < small medium large > \s+ < cup mug > $");
+    assert!( re.is_match("medium mug"));
+    assert!(!re.is_match("medium glass"));
+    assert!(!re.is_match("huge cup"));
+}
+#[test]
+fn many_words() {
+    let words: Vec<String> = (0..5000).map(|n| format!("item{}", n)).collect();
+    let re = Regex::new(&format!("< {} >", words.join(" ")));
+    let txt = "no match here ".repeat(200) + "buy item4999 and item42";
+
+    // Longest wins so `item4999` isn't cut short at `item4`.
+    assert_eq!("item4999", re.find(&txt).unwrap().as_str());
+    assert!(!re.is_match(&"item".repeat(500)));
+}
+#[test]
+fn class_operand() {
+    let re = Regex::new(r"^ < alpha + [_] > $");
+    assert!( re.is_match("a"));
+    assert!( re.is_match("_"));
+    assert!(!re.is_match("alpha"));
+}
@@ -0,0 +1,46 @@
+use rules::parse::Fold;
+use rules::range_set::{Range, Set};
+use rules::trie::Trie;
+
+fn generate(words: &[&str]) -> Trie {
+    let mut trie = Trie::new();
+
+    for word in words { trie.insert(word) }
+
+    trie
+}
+
+fn walk(trie: &Trie, txt: &str, fold: Fold) -> Vec<usize> {
+    trie.walk(fold.chars(txt))
+}
+
+#[test]
+fn words() {
+    let trie = generate(&["cart", "c", "car", "car"]);
+
+    // Shortest first no matter the order they were added.
+    assert_eq!(vec![1, 3, 4], walk(&trie, "carton", Fold::default()));
+    assert_eq!(vec![1], walk(&trie, "cab", Fold::default()));
+    assert!(walk(&trie, "dog", Fold::default()).is_empty());
+    assert!(walk(&trie, "", Fold::default()).is_empty());
+    // Lengths are in bytes.
+    assert_eq!(vec![2, 5], walk(&generate(&["ñ", "ñañ"]), "ñañ", Fold::default()));
+}
+#[test]
+fn folded() {
+    let case = Fold { case: true, ..Fold::default() };
+    let marks = Fold { marks: true, ..Fold::default() };
+
+    // A word only ends once the whole char it came from is used up.
+    assert_eq!(vec![2], walk(&generate(&["s", "ss"]), "ß", case));
+    assert_eq!(vec![3, 4], walk(&generate(&["e", "ee"]), "e\u{301}e", marks));
+    assert_eq!(vec![3], walk(&generate(&["e"]), "e\u{301}", marks));
+}
+#[test]
+fn first_chars() {
+    let mut set = Set::new();
+    set.insert(Range('a', 'b'));
+    set.insert(Range('z', 'z'));
+
+    assert_eq!(set, generate(&["big", "zoo", "apple", "bag"]).first_chars());
+}
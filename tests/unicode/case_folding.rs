@@ -0,0 +1,17 @@
+use rules::unicode::case_folding::{C_plus_S_table, F_table};
+
+#[test]
+fn sorted() {
+    // Both are binary searched by the char being folded.
+    assert!(C_plus_S_table.windows(2).all(|w| w[0].0 < w[1].0));
+    assert!(F_table.windows(2).all(|w| w[0].0 < w[1].0));
+}
+#[test]
+fn full() {
+    let find = |c| F_table.iter().find(|&&(from, _)| from == c).map(|&(_, to)| to);
+
+    assert_eq!(Some("ss"), find('ß'));
+    assert_eq!(Some("fi"), find('ﬁ'));
+    assert_eq!(Some("i\u{307}"), find('İ'));
+    assert_eq!(None, find('a'));
+}
@@ -0,0 +1,17 @@
+use rules::unicode::decomposition::Canonical_table;
+
+#[test]
+fn sorted() {
+    assert!(Canonical_table.windows(2).all(|w| w[0].0 < w[1].0));
+}
+#[test]
+fn full() {
+    let find = |c| Canonical_table.iter().find(|&&(from, _)| from == c).map(|&(_, to)| to);
+
+    assert_eq!(Some("e\u{301}"), find('é'));
+    // Decomposed all the way down rather than one step.
+    assert_eq!(Some("e\u{302}\u{301}"), find('ế'));
+    assert_eq!(None, find('e'));
+    // Hangul syllables only decompose into jamo which aren't marks.
+    assert_eq!(None, find('한'));
+}
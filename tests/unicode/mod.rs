@@ -0,0 +1,3 @@
+mod case_folding;
+mod decomposition;
+mod name;
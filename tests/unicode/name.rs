@@ -0,0 +1,42 @@
+use rules::unicode::name::lookup;
+
+#[test]
+fn names() {
+    assert_eq!(Some('a'), lookup("LATIN SMALL LETTER A"));
+    assert_eq!(Some('á'), lookup("LATIN SMALL LETTER A WITH ACUTE"));
+    assert_eq!(Some('α'), lookup("GREEK SMALL LETTER ALPHA"));
+    assert_eq!(Some('☺'), lookup("WHITE SMILING FACE"));
+    assert_eq!(None,      lookup("LATIN SMALL LETTER NOPE"));
+}
+#[test]
+fn loose() {
+    assert_eq!(Some('α'), lookup("greek small letter alpha"));
+    assert_eq!(Some('α'), lookup("Greek_Small_Letter_Alpha"));
+    assert_eq!(Some('α'), lookup("  GREEK  SMALL LETTER ALPHA "));
+}
+#[test]
+fn aliases() {
+    assert_eq!(Some('\n'),       lookup("LINE FEED"));
+    assert_eq!(Some('\n'),       lookup("LF"));
+    assert_eq!(Some('\u{feff}'), lookup("BYTE ORDER MARK"));
+    // A correction of a misspelled name.
+    assert_eq!(Some('\u{1d0c5}'), lookup("BYZANTINE MUSICAL SYMBOL FTHORA SKLIRON CHROMA VASIS"));
+}
+#[test]
+fn ideographs() {
+    assert_eq!(Some('中'),         lookup("CJK UNIFIED IDEOGRAPH-4E2D"));
+    assert_eq!(Some('\u{20000}'), lookup("CJK UNIFIED IDEOGRAPH-20000"));
+    assert_eq!(Some('\u{f900}'),  lookup("CJK COMPATIBILITY IDEOGRAPH-F900"));
+    assert_eq!(Some('\u{17000}'), lookup("TANGUT IDEOGRAPH-17000"));
+    // Outside of the named ranges.
+    assert_eq!(None,              lookup("CJK UNIFIED IDEOGRAPH-0041"));
+    assert_eq!(None,              lookup("CJK UNIFIED IDEOGRAPH-+4E2D"));
+}
+#[test]
+fn hangul() {
+    assert_eq!(Some('가'), lookup("HANGUL SYLLABLE GA"));
+    assert_eq!(Some('각'), lookup("HANGUL SYLLABLE GAG"));
+    assert_eq!(Some('힣'), lookup("HANGUL SYLLABLE HIH"));
+    assert_eq!(Some('아'), lookup("hangul syllable a"));
+    assert_eq!(None,       lookup("HANGUL SYLLABLE GX"));
+}
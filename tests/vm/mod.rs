@@ -0,0 +1,10 @@
+use rules::{collapse, compile, parse, vm};
+
+// The `(start, end)` of the leftmost match.
+fn search(re: &str, txt: &str) -> Option<(usize, usize)> {
+    let vec = collapse::collapse(parse::parse(re).unwrap());
+
+    vm::search(&compile::compile(&vec), txt)
+}
+
+mod search;
@@ -0,0 +1,143 @@
+use super::search;
+
+#[test]
+fn leftmost() {
+    assert_eq!(Some((2, 4)), search(r"cd", "abcdcd"));
+    assert_eq!(Some((1, 4)), search(r"\d+", "a123b45"));
+    assert_eq!(None,         search(r"\d+", "abc"));
+}
+#[test]
+fn longest_token() {
+    // `|` picks the longest branch no matter the order.
+    assert_eq!(Some((0, 6)), search(r"'foo' | 'foobar'", "foobar"));
+    assert_eq!(Some((0, 6)), search(r"'foobar' | 'foo'", "foobar"));
+    assert_eq!(Some((0, 3)), search(r"'foo' | \w+", "foo bar"));
+    assert_eq!(Some((0, 4)), search(r"'if' | \w+", "iffy bar"));
+}
+#[test]
+fn longest_token_must_match() {
+    // A branch is only longest if it actually matches.
+    assert_eq!(Some((0, 2)), search(r"'ab' | 'abc'", "abd"));
+    assert_eq!(Some((0, 2)), search(r"'abc' | 'ab'", "abd"));
+}
+#[test]
+fn sequential() {
+    // `||` takes the first branch which matches.
+    assert_eq!(Some((0, 3)), search(r"'foo' || 'foobar'", "foobar"));
+    assert_eq!(Some((0, 6)), search(r"'foobar' || 'foo'", "foobar"));
+}
+#[test]
+fn longest_token_backtracks() {
+    // The longest branch fails afterwards so the next longest is tried.
+    assert_eq!(Some((0, 3)), search(r"[ 'ab' | 'abc' ] c", "abcd"));
+    assert_eq!(Some((0, 4)), search(r"[ 'ab' | 'abc' ] d", "abcd"));
+}
+#[test]
+fn sequential_ends_prefix() {
+    // Only the part before `||` is used to pick the longest branch.
+    assert_eq!(Some((0, 2)), search(r"'ab' | a [ b || bcd ]", "abcd"));
+    assert_eq!(Some((0, 2)), search(r"a [ b || bcd ] | 'ab'", "abcd"));
+}
+#[test]
+fn groups() {
+    assert_eq!(Some((1, 7)), search(r"[ab]+", "cababab"));
+    assert_eq!(Some((0, 4)), search(r"(a | b) ** 2..4", "abbab"));
+    assert_eq!(Some((0, 5)), search(r"[ x || xy ]+ z", "xyxxz"));
+}
+#[test]
+fn lookahead() {
+    assert_eq!(Some((0, 3)), search(r"foo <?after bar>", "foobar"));
+    assert_eq!(None,         search(r"foo <?after bar>", "foobaz"));
+    assert_eq!(Some((0, 3)), search(r"foo <!after bar>", "foobaz"));
+    assert_eq!(Some((6, 9)), search(r"foo <!after bar>", "foobarfoo"));
+}
+#[test]
+fn lookbehind() {
+    assert_eq!(Some((3, 6)), search(r"<?before foo> bar", "foobar"));
+    assert_eq!(None,         search(r"<?before foo> bar", "sushibar"));
+    assert_eq!(Some((5, 8)), search(r"<!before foo> bar", "sushibar"));
+    // An unbounded lookbehind can start anywhere before.
+    assert_eq!(Some((3, 4)), search(r"<?before a \d+> x", "a12x3x"));
+}
+#[test]
+fn lookbehind_backwards() {
+    // Everything inside a lookbehind is matched from right to left.
+    assert_eq!(Some((4, 5)), search(r"<?before [ab | c]+ d> x", "abcdx"));
+    assert_eq!(None,         search(r"<?before [ab | c]+ d> x", "abxdx"));
+    assert_eq!(Some((7, 8)), search(r"<?before ('ab') ** 2> x", "abxababx"));
+    assert_eq!(Some((5, 6)), search(r"<?before < foo bar >> x", "x barx"));
+    assert_eq!(Some((4, 5)), search(r"<?before <?before a> b \n> x", "ab\r\nx"));
+    // It still has to match the same way forwards.
+    assert_eq!(Some((2, 3)), search(r"<?before \r \n> x", "\r\nx"));
+    assert_eq!(Some((3, 4)), search(r"<?before :m e> x", "e\u{301}x"));
+    assert_eq!(None,         search(r"<?before :m e> .", "e\u{301}"));
+}
+#[test]
+fn lookbehind_long_text() {
+    // An unbounded lookbehind doesn't go all the way back every time.
+    let txt = "ab ".repeat(2000);
+    assert_eq!(Some((2, 3)), search(r"<?before \w+> ' '", &txt));
+    assert_eq!(None,         search(r"<!before \w+> ' '", &txt));
+    assert_eq!(None,         search(r"<?before 'c' [\w | ' ']*> ' '", &txt));
+}
+#[test]
+fn lookaround_both() {
+    assert_eq!(Some((3, 6)), search(r"<?before foo> bar <?after baz>", "foobarbaz"));
+}
+#[test]
+fn string_anchors() {
+    assert_eq!(Some((0, 3)), search(r"^ abc", "abcabc"));
+    assert_eq!(None,         search(r"^ abc", "xabc"));
+    assert_eq!(Some((3, 6)), search(r"abc $", "abcabc"));
+    assert_eq!(None,         search(r"abc $", "abcx"));
+    assert_eq!(Some((0, 0)), search(r"^ $", ""));
+}
+#[test]
+fn line_anchors() {
+    assert_eq!(Some((4, 7)), search(r"^^ def", "abc\ndef"));
+    assert_eq!(None,         search(r"^^ bc", "abc\ndef"));
+    assert_eq!(Some((0, 3)), search(r"abc $$", "abc\ndef"));
+    assert_eq!(Some((4, 7)), search(r"^^ \w+ $$", "a b\ndef\n"));
+    // A newline at the very end doesn't start another line.
+    assert_eq!(None,         search(r"\n ^^", "abc\n"));
+    assert_eq!(Some((3, 3)), search(r"$$", "abc\n"));
+    // Any newline `\n` matches ends a line and `\r\n` is only one.
+    assert_eq!(Some((0, 3)), search(r"abc $$", "abc\r\n"));
+    assert_eq!(Some((5, 8)), search(r"^^ def $$", "abc\r\ndef\r\n"));
+    assert_eq!(None,         search(r"^^ \n", "abc\r\n"));
+    assert_eq!(None,         search(r"\r $$", "abc\r\n"));
+    assert_eq!(Some((2, 3)), search(r"^^ b", "a\rb"));
+    assert_eq!(Some((4, 5)), search(r"^^ b", "a\u{2028}b"));
+    assert_eq!(Some((0, 1)), search(r"a $$", "a\u{2028}b"));
+    assert_eq!(Some((3, 3)), search(r"$$", "abc\r\n"));
+}
+#[test]
+fn word_boundaries() {
+    assert_eq!(Some((5, 8)), search(r"<< cat >>", "cats cat"));
+    assert_eq!(Some((7, 10)), search(r"« cat »", "bobcat cat"));
+    assert_eq!(Some((0, 0)), search(r"<wb>", "cat"));
+    assert_eq!(Some((3, 3)), search(r"\w <wb>", "cat dog").map(|(_, end)| (end, end)));
+    assert_eq!(None,         search(r"<< at", "cat"));
+    // Word chars aren't only ASCII.
+    assert_eq!(Some((8, 10)), search(r"<< é", "caféé é"));
+}
+#[test]
+fn dot() {
+    assert_eq!(Some((0, 3)), search(r"a . c", "abc"));
+    assert_eq!(Some((0, 4)), search(r"a . c", "aéc"));
+    assert_eq!(Some((0, 3)), search(r"a . c", "a\nc"));
+    assert_eq!(None,         search(r"a . c", "ac"));
+    assert_eq!(None,         search(r".", ""));
+    assert_eq!(Some((0, 0)), search(r".*", ""));
+}
+#[test]
+fn newline() {
+    assert_eq!(Some((1, 2)), search(r"\n", "a\nb"));
+    assert_eq!(Some((1, 3)), search(r"\n", "a\r\nb"));
+    assert_eq!(Some((1, 2)), search(r"\n", "a\rb"));
+    assert_eq!(Some((1, 4)), search(r"\n", "a\u{2028}b"));
+    // `\r\n` is a single newline so `\N` can't match half of it.
+    assert_eq!(None,         search(r"a \n \n b", "a\r\nb"));
+    assert_eq!(Some((0, 4)), search(r"a \n \n b", "a\r\rb"));
+    assert_eq!(None,         search(r"\N", "\r\n"));
+}